@@ -0,0 +1,18 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use gs1::epc::{decode_binary, is_valid_epc};
+
+const SGTIN96: [u8; 12] = [
+    0x30, 0x74, 0x25, 0x7B, 0xF7, 0x19, 0x4E, 0x40, 0x00, 0x00, 0x1A, 0x85,
+];
+
+fn bench_decode(c: &mut Criterion) {
+    c.bench_function("decode_binary sgtin-96", |b| {
+        b.iter(|| decode_binary(black_box(&SGTIN96)).is_ok())
+    });
+    c.bench_function("is_valid_epc sgtin-96", |b| {
+        b.iter(|| is_valid_epc(black_box(&SGTIN96)))
+    });
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
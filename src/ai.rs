@@ -0,0 +1,555 @@
+//! GS1 Application Identifiers
+use crate::error::{Gs1Error, Result};
+use crate::util::is_cset82;
+use alloc::{format, string::String};
+use core::convert::TryFrom;
+use core::str::FromStr;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// GS1 Application Identifiers, which identify the meaning of each element in a GS1 element
+/// string or Digital Link URI.
+///
+/// This covers the commonly-used AIs, rather than the full GS1 table.
+// GS1 General Specifications, Figure 3.2-1
+#[repr(u16)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, IntoPrimitive, TryFromPrimitive)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ApplicationIdentifier {
+    SSCC = 0,
+    GTIN = 1,
+    GTINContent = 2,
+    Batch = 10,
+    ProductionDate = 11,
+    DueDate = 12,
+    PackagingDate = 13,
+    BestBeforeDate = 15,
+    SellByDate = 16,
+    ExpirationDate = 17,
+    InternalProductVariant = 20,
+    SerialNumber = 21,
+    ConsumerProductVariant = 22,
+    VariableCount = 30,
+    ContentCount = 37,
+    MutuallyAgreed = 90,
+    CompanyInternal1 = 91,
+    CompanyInternal2 = 92,
+    CompanyInternal3 = 93,
+    CompanyInternal4 = 94,
+    CompanyInternal5 = 95,
+    CompanyInternal6 = 96,
+    CompanyInternal7 = 97,
+    CompanyInternal8 = 98,
+    CompanyInternal9 = 99,
+    ThirdPartySerial = 235,
+    AdditionalProductId = 240,
+    CustomerPartNumber = 241,
+    MadeToOrderVariant = 242,
+    PackagingComponent = 243,
+    SecondarySerial = 250,
+    SourceEntity = 251,
+    GDTI = 253,
+    GLNExtension = 254,
+    GCN = 255,
+    OrderNumber = 400,
+    GINC = 401,
+    GSIN = 402,
+    Route = 403,
+    ShipTo = 410,
+    BillTo = 411,
+    PurchasedFrom = 412,
+    ShipFor = 413,
+    LocationGLN = 414,
+    PayTo = 415,
+    ProductionLocation = 416,
+    ShipToPostalCode = 420,
+    ShipToPostalCodeISO = 421,
+    CountryOfOrigin = 422,
+    // Trade and logistic measures. The last digit of the AI is the number of decimal places in
+    // the value.
+    NetWeightKg0 = 3100,
+    NetWeightKg1 = 3101,
+    NetWeightKg2 = 3102,
+    NetWeightKg3 = 3103,
+    NetWeightKg4 = 3104,
+    NetWeightKg5 = 3105,
+    LengthM0 = 3110,
+    LengthM1 = 3111,
+    LengthM2 = 3112,
+    LengthM3 = 3113,
+    LengthM4 = 3114,
+    LengthM5 = 3115,
+    WidthM0 = 3120,
+    WidthM1 = 3121,
+    WidthM2 = 3122,
+    WidthM3 = 3123,
+    WidthM4 = 3124,
+    WidthM5 = 3125,
+    HeightM0 = 3130,
+    HeightM1 = 3131,
+    HeightM2 = 3132,
+    HeightM3 = 3133,
+    HeightM4 = 3134,
+    HeightM5 = 3135,
+    AreaM20 = 3140,
+    AreaM21 = 3141,
+    AreaM22 = 3142,
+    AreaM23 = 3143,
+    AreaM24 = 3144,
+    AreaM25 = 3145,
+    NetVolumeL0 = 3150,
+    NetVolumeL1 = 3151,
+    NetVolumeL2 = 3152,
+    NetVolumeL3 = 3153,
+    NetVolumeL4 = 3154,
+    NetVolumeL5 = 3155,
+    NetVolumeM30 = 3160,
+    NetVolumeM31 = 3161,
+    NetVolumeM32 = 3162,
+    NetVolumeM33 = 3163,
+    NetVolumeM34 = 3164,
+    NetVolumeM35 = 3165,
+    NetWeightLb0 = 3200,
+    NetWeightLb1 = 3201,
+    NetWeightLb2 = 3202,
+    NetWeightLb3 = 3203,
+    NetWeightLb4 = 3204,
+    NetWeightLb5 = 3205,
+    LengthIn0 = 3210,
+    LengthIn1 = 3211,
+    LengthIn2 = 3212,
+    LengthIn3 = 3213,
+    LengthIn4 = 3214,
+    LengthIn5 = 3215,
+    LengthFt0 = 3220,
+    LengthFt1 = 3221,
+    LengthFt2 = 3222,
+    LengthFt3 = 3223,
+    LengthFt4 = 3224,
+    LengthFt5 = 3225,
+    LengthYd0 = 3230,
+    LengthYd1 = 3231,
+    LengthYd2 = 3232,
+    LengthYd3 = 3233,
+    LengthYd4 = 3234,
+    LengthYd5 = 3235,
+    WidthIn0 = 3240,
+    WidthIn1 = 3241,
+    WidthIn2 = 3242,
+    WidthIn3 = 3243,
+    WidthIn4 = 3244,
+    WidthIn5 = 3245,
+    WidthFt0 = 3250,
+    WidthFt1 = 3251,
+    WidthFt2 = 3252,
+    WidthFt3 = 3253,
+    WidthFt4 = 3254,
+    WidthFt5 = 3255,
+    WidthYd0 = 3260,
+    WidthYd1 = 3261,
+    WidthYd2 = 3262,
+    WidthYd3 = 3263,
+    WidthYd4 = 3264,
+    WidthYd5 = 3265,
+    HeightIn0 = 3270,
+    HeightIn1 = 3271,
+    HeightIn2 = 3272,
+    HeightIn3 = 3273,
+    HeightIn4 = 3274,
+    HeightIn5 = 3275,
+    HeightFt0 = 3280,
+    HeightFt1 = 3281,
+    HeightFt2 = 3282,
+    HeightFt3 = 3283,
+    HeightFt4 = 3284,
+    HeightFt5 = 3285,
+    HeightYd0 = 3290,
+    HeightYd1 = 3291,
+    HeightYd2 = 3292,
+    HeightYd3 = 3293,
+    HeightYd4 = 3294,
+    HeightYd5 = 3295,
+    GrossWeightKg0 = 3300,
+    GrossWeightKg1 = 3301,
+    GrossWeightKg2 = 3302,
+    GrossWeightKg3 = 3303,
+    GrossWeightKg4 = 3304,
+    GrossWeightKg5 = 3305,
+    LogisticLengthM0 = 3310,
+    LogisticLengthM1 = 3311,
+    LogisticLengthM2 = 3312,
+    LogisticLengthM3 = 3313,
+    LogisticLengthM4 = 3314,
+    LogisticLengthM5 = 3315,
+    LogisticWidthM0 = 3320,
+    LogisticWidthM1 = 3321,
+    LogisticWidthM2 = 3322,
+    LogisticWidthM3 = 3323,
+    LogisticWidthM4 = 3324,
+    LogisticWidthM5 = 3325,
+    LogisticHeightM0 = 3330,
+    LogisticHeightM1 = 3331,
+    LogisticHeightM2 = 3332,
+    LogisticHeightM3 = 3333,
+    LogisticHeightM4 = 3334,
+    LogisticHeightM5 = 3335,
+    LogisticAreaM20 = 3340,
+    LogisticAreaM21 = 3341,
+    LogisticAreaM22 = 3342,
+    LogisticAreaM23 = 3343,
+    LogisticAreaM24 = 3344,
+    LogisticAreaM25 = 3345,
+    LogisticVolumeL0 = 3350,
+    LogisticVolumeL1 = 3351,
+    LogisticVolumeL2 = 3352,
+    LogisticVolumeL3 = 3353,
+    LogisticVolumeL4 = 3354,
+    LogisticVolumeL5 = 3355,
+    LogisticVolumeM30 = 3360,
+    LogisticVolumeM31 = 3361,
+    LogisticVolumeM32 = 3362,
+    LogisticVolumeM33 = 3363,
+    LogisticVolumeM34 = 3364,
+    LogisticVolumeM35 = 3365,
+    KgPerM20 = 3370,
+    KgPerM21 = 3371,
+    KgPerM22 = 3372,
+    KgPerM23 = 3373,
+    KgPerM24 = 3374,
+    KgPerM25 = 3375,
+    GrossWeightLb0 = 3400,
+    GrossWeightLb1 = 3401,
+    GrossWeightLb2 = 3402,
+    GrossWeightLb3 = 3403,
+    GrossWeightLb4 = 3404,
+    GrossWeightLb5 = 3405,
+    LogisticLengthIn0 = 3410,
+    LogisticLengthIn1 = 3411,
+    LogisticLengthIn2 = 3412,
+    LogisticLengthIn3 = 3413,
+    LogisticLengthIn4 = 3414,
+    LogisticLengthIn5 = 3415,
+    LogisticLengthFt0 = 3420,
+    LogisticLengthFt1 = 3421,
+    LogisticLengthFt2 = 3422,
+    LogisticLengthFt3 = 3423,
+    LogisticLengthFt4 = 3424,
+    LogisticLengthFt5 = 3425,
+    LogisticLengthYd0 = 3430,
+    LogisticLengthYd1 = 3431,
+    LogisticLengthYd2 = 3432,
+    LogisticLengthYd3 = 3433,
+    LogisticLengthYd4 = 3434,
+    LogisticLengthYd5 = 3435,
+    LogisticWidthIn0 = 3440,
+    LogisticWidthIn1 = 3441,
+    LogisticWidthIn2 = 3442,
+    LogisticWidthIn3 = 3443,
+    LogisticWidthIn4 = 3444,
+    LogisticWidthIn5 = 3445,
+    LogisticWidthFt0 = 3450,
+    LogisticWidthFt1 = 3451,
+    LogisticWidthFt2 = 3452,
+    LogisticWidthFt3 = 3453,
+    LogisticWidthFt4 = 3454,
+    LogisticWidthFt5 = 3455,
+    LogisticWidthYd0 = 3460,
+    LogisticWidthYd1 = 3461,
+    LogisticWidthYd2 = 3462,
+    LogisticWidthYd3 = 3463,
+    LogisticWidthYd4 = 3464,
+    LogisticWidthYd5 = 3465,
+    LogisticHeightIn0 = 3470,
+    LogisticHeightIn1 = 3471,
+    LogisticHeightIn2 = 3472,
+    LogisticHeightIn3 = 3473,
+    LogisticHeightIn4 = 3474,
+    LogisticHeightIn5 = 3475,
+    LogisticHeightFt0 = 3480,
+    LogisticHeightFt1 = 3481,
+    LogisticHeightFt2 = 3482,
+    LogisticHeightFt3 = 3483,
+    LogisticHeightFt4 = 3484,
+    LogisticHeightFt5 = 3485,
+    LogisticHeightYd0 = 3490,
+    LogisticHeightYd1 = 3491,
+    LogisticHeightYd2 = 3492,
+    LogisticHeightYd3 = 3493,
+    LogisticHeightYd4 = 3494,
+    LogisticHeightYd5 = 3495,
+    AreaIn20 = 3500,
+    AreaIn21 = 3501,
+    AreaIn22 = 3502,
+    AreaIn23 = 3503,
+    AreaIn24 = 3504,
+    AreaIn25 = 3505,
+    AreaFt20 = 3510,
+    AreaFt21 = 3511,
+    AreaFt22 = 3512,
+    AreaFt23 = 3513,
+    AreaFt24 = 3514,
+    AreaFt25 = 3515,
+    AreaYd20 = 3520,
+    AreaYd21 = 3521,
+    AreaYd22 = 3522,
+    AreaYd23 = 3523,
+    AreaYd24 = 3524,
+    AreaYd25 = 3525,
+    LogisticAreaIn20 = 3530,
+    LogisticAreaIn21 = 3531,
+    LogisticAreaIn22 = 3532,
+    LogisticAreaIn23 = 3533,
+    LogisticAreaIn24 = 3534,
+    LogisticAreaIn25 = 3535,
+    LogisticAreaFt20 = 3540,
+    LogisticAreaFt21 = 3541,
+    LogisticAreaFt22 = 3542,
+    LogisticAreaFt23 = 3543,
+    LogisticAreaFt24 = 3544,
+    LogisticAreaFt25 = 3545,
+    LogisticAreaYd20 = 3550,
+    LogisticAreaYd21 = 3551,
+    LogisticAreaYd22 = 3552,
+    LogisticAreaYd23 = 3553,
+    LogisticAreaYd24 = 3554,
+    LogisticAreaYd25 = 3555,
+    NetWeightTroyOz0 = 3560,
+    NetWeightTroyOz1 = 3561,
+    NetWeightTroyOz2 = 3562,
+    NetWeightTroyOz3 = 3563,
+    NetWeightTroyOz4 = 3564,
+    NetWeightTroyOz5 = 3565,
+    NetVolumeOz0 = 3570,
+    NetVolumeOz1 = 3571,
+    NetVolumeOz2 = 3572,
+    NetVolumeOz3 = 3573,
+    NetVolumeOz4 = 3574,
+    NetVolumeOz5 = 3575,
+    NetVolumeQt0 = 3600,
+    NetVolumeQt1 = 3601,
+    NetVolumeQt2 = 3602,
+    NetVolumeQt3 = 3603,
+    NetVolumeQt4 = 3604,
+    NetVolumeQt5 = 3605,
+    NetVolumeGal0 = 3610,
+    NetVolumeGal1 = 3611,
+    NetVolumeGal2 = 3612,
+    NetVolumeGal3 = 3613,
+    NetVolumeGal4 = 3614,
+    NetVolumeGal5 = 3615,
+    LogisticVolumeQt0 = 3620,
+    LogisticVolumeQt1 = 3621,
+    LogisticVolumeQt2 = 3622,
+    LogisticVolumeQt3 = 3623,
+    LogisticVolumeQt4 = 3624,
+    LogisticVolumeQt5 = 3625,
+    LogisticVolumeGal0 = 3630,
+    LogisticVolumeGal1 = 3631,
+    LogisticVolumeGal2 = 3632,
+    LogisticVolumeGal3 = 3633,
+    LogisticVolumeGal4 = 3634,
+    LogisticVolumeGal5 = 3635,
+    VolumeIn30 = 3640,
+    VolumeIn31 = 3641,
+    VolumeIn32 = 3642,
+    VolumeIn33 = 3643,
+    VolumeIn34 = 3644,
+    VolumeIn35 = 3645,
+    VolumeFt30 = 3650,
+    VolumeFt31 = 3651,
+    VolumeFt32 = 3652,
+    VolumeFt33 = 3653,
+    VolumeFt34 = 3654,
+    VolumeFt35 = 3655,
+    VolumeYd30 = 3660,
+    VolumeYd31 = 3661,
+    VolumeYd32 = 3662,
+    VolumeYd33 = 3663,
+    VolumeYd34 = 3664,
+    VolumeYd35 = 3665,
+    LogisticVolumeIn30 = 3670,
+    LogisticVolumeIn31 = 3671,
+    LogisticVolumeIn32 = 3672,
+    LogisticVolumeIn33 = 3673,
+    LogisticVolumeIn34 = 3674,
+    LogisticVolumeIn35 = 3675,
+    LogisticVolumeFt30 = 3680,
+    LogisticVolumeFt31 = 3681,
+    LogisticVolumeFt32 = 3682,
+    LogisticVolumeFt33 = 3683,
+    LogisticVolumeFt34 = 3684,
+    LogisticVolumeFt35 = 3685,
+    LogisticVolumeYd30 = 3690,
+    LogisticVolumeYd31 = 3691,
+    LogisticVolumeYd32 = 3692,
+    LogisticVolumeYd33 = 3693,
+    LogisticVolumeYd34 = 3694,
+    LogisticVolumeYd35 = 3695,
+    // Prices of variable measure trade items, alone or after an ISO 4217 currency code. The last
+    // digit of the AI is the number of decimal places in the price.
+    Price0 = 3920,
+    Price1 = 3921,
+    Price2 = 3922,
+    Price3 = 3923,
+    Price4 = 3924,
+    Price5 = 3925,
+    Price6 = 3926,
+    Price7 = 3927,
+    Price8 = 3928,
+    Price9 = 3929,
+    PriceISO0 = 3930,
+    PriceISO1 = 3931,
+    PriceISO2 = 3932,
+    PriceISO3 = 3933,
+    PriceISO4 = 3934,
+    PriceISO5 = 3935,
+    PriceISO6 = 3936,
+    PriceISO7 = 3937,
+    PriceISO8 = 3938,
+    PriceISO9 = 3939,
+    ExpiryTime = 7003,
+    GRAI = 8003,
+    GIAI = 8004,
+    ITIP = 8006,
+    GSRNProvider = 8017,
+    GSRNRecipient = 8018,
+    ServiceRelationInstance = 8019,
+    PaymentSlipReference = 8020,
+}
+
+/// The format of the data field of an AI.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct AiSpec {
+    /// Whether the data field always has `max_length` characters
+    pub fixed_length: bool,
+    /// The maximum number of characters in the data field
+    pub max_length: usize,
+    /// Whether the data field may only contain digits. Otherwise, it may contain any character
+    /// from GS1 character set 82.
+    pub numeric: bool,
+}
+
+impl AiSpec {
+    const fn fixed_numeric(length: usize) -> AiSpec {
+        AiSpec {
+            fixed_length: true,
+            max_length: length,
+            numeric: true,
+        }
+    }
+
+    const fn variable_numeric(max_length: usize) -> AiSpec {
+        AiSpec {
+            fixed_length: false,
+            max_length,
+            numeric: true,
+        }
+    }
+
+    const fn alphanumeric(max_length: usize) -> AiSpec {
+        AiSpec {
+            fixed_length: false,
+            max_length,
+            numeric: false,
+        }
+    }
+
+    /// Whether a value is a valid data field for this AI.
+    pub fn is_valid(&self, value: &str) -> bool {
+        let length = value.chars().count();
+        if length == 0 || length > self.max_length {
+            return false;
+        }
+        if self.fixed_length && length != self.max_length {
+            return false;
+        }
+        if self.numeric {
+            value.chars().all(|c| c.is_ascii_digit())
+        } else {
+            value.chars().all(is_cset82)
+        }
+    }
+}
+
+impl ApplicationIdentifier {
+    // The number of digits in the AI. AIs below 100 are written with a leading zero, and no AI
+    // is a prefix of another.
+    pub(crate) fn digits(value: u16) -> usize {
+        match value {
+            0..=99 => 2,
+            100..=999 => 3,
+            _ => 4,
+        }
+    }
+
+    // Return the AI as it's written in element strings, such as `01` or `8003`.
+    pub(crate) fn to_digits(self) -> String {
+        let value = self as u16;
+        format!(
+            "{:0width$}",
+            value,
+            width = ApplicationIdentifier::digits(value)
+        )
+    }
+
+    /// Return the format of this AI's data field.
+    ///
+    /// Data fields which combine a numeric and alphanumeric part (such as the GDTI) are treated
+    /// as alphanumeric with the combined maximum length.
+    // GS1 General Specifications, Figure 3.2-1
+    pub fn spec(&self) -> AiSpec {
+        use ApplicationIdentifier::*;
+        match self {
+            SSCC => AiSpec::fixed_numeric(18),
+            GTIN | GTINContent => AiSpec::fixed_numeric(14),
+            ProductionDate | DueDate | PackagingDate | BestBeforeDate | SellByDate
+            | ExpirationDate => AiSpec::fixed_numeric(6),
+            InternalProductVariant => AiSpec::fixed_numeric(2),
+            Batch | SerialNumber | ConsumerProductVariant => AiSpec::alphanumeric(20),
+            VariableCount | ContentCount => AiSpec::variable_numeric(8),
+            // AI 90 is for information mutually agreed between trading partners, and AIs 91-99
+            // are for company internal information. Their contents aren't interpreted.
+            MutuallyAgreed => AiSpec::alphanumeric(30),
+            CompanyInternal1 | CompanyInternal2 | CompanyInternal3 | CompanyInternal4
+            | CompanyInternal5 | CompanyInternal6 | CompanyInternal7 | CompanyInternal8
+            | CompanyInternal9 => AiSpec::alphanumeric(90),
+            ThirdPartySerial => AiSpec::alphanumeric(28),
+            AdditionalProductId | CustomerPartNumber | SecondarySerial | SourceEntity
+            | OrderNumber | GINC | Route | GIAI => AiSpec::alphanumeric(30),
+            MadeToOrderVariant => AiSpec::variable_numeric(6),
+            PackagingComponent | GLNExtension | ShipToPostalCode => AiSpec::alphanumeric(20),
+            GDTI | GRAI => AiSpec::alphanumeric(30),
+            GCN => AiSpec::variable_numeric(25),
+            GSIN => AiSpec::fixed_numeric(17),
+            ShipTo | BillTo | PurchasedFrom | ShipFor | LocationGLN | PayTo
+            | ProductionLocation => AiSpec::fixed_numeric(13),
+            ShipToPostalCodeISO => AiSpec::alphanumeric(12),
+            CountryOfOrigin => AiSpec::fixed_numeric(3),
+            ExpiryTime => AiSpec::fixed_numeric(10),
+            ITIP | GSRNProvider | GSRNRecipient => AiSpec::fixed_numeric(18),
+            ServiceRelationInstance => AiSpec::variable_numeric(10),
+            PaymentSlipReference => AiSpec::alphanumeric(25),
+            Price0 | Price1 | Price2 | Price3 | Price4 | Price5 | Price6 | Price7 | Price8
+            | Price9 => AiSpec::variable_numeric(15),
+            PriceISO0 | PriceISO1 | PriceISO2 | PriceISO3 | PriceISO4 | PriceISO5 | PriceISO6
+            | PriceISO7 | PriceISO8 | PriceISO9 => AiSpec::variable_numeric(18),
+            // The measures, AIs 310n-369n
+            _ => AiSpec::fixed_numeric(6),
+        }
+    }
+}
+
+/// Parse an AI from its digits, such as `01` or `8003`.
+impl FromStr for ApplicationIdentifier {
+    type Err = Gs1Error;
+
+    fn from_str(input: &str) -> Result<ApplicationIdentifier> {
+        if !input.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Gs1Error::Parse);
+        }
+        let value = input.parse::<u16>()?;
+        if ApplicationIdentifier::digits(value) != input.len() {
+            return Err(Gs1Error::Parse);
+        }
+        ApplicationIdentifier::try_from(value).map_err(|_| Gs1Error::Parse)
+    }
+}
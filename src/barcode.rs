@@ -0,0 +1,37 @@
+//! GS1-128 barcodes
+//!
+//! GS1-128 is the subset of Code 128 used to carry GS1 element strings. The elements are
+//! concatenated, with FNC1 separating each variable-length element from the next.
+use crate::{has_predefined_length, ApplicationIdentifier, FNC1};
+use alloc::string::String;
+
+/// Produce the data string for a GS1-128 barcode from a list of AIs and their values.
+///
+/// Elements are written in the order given. Each element is followed by `FNC1` unless it's the
+/// last element or its AI has a predefined length, so the output can be passed straight to a Code
+/// 128 renderer. The FNC1 which starts every GS1-128 symbol isn't included. Values aren't
+/// validated against their AI; use `combine_gs1` to validate and order elements.
+///
+/// # Example
+/// ```
+/// # use gs1::barcode::to_gs1_128;
+/// # use gs1::ApplicationIdentifier;
+/// let data = to_gs1_128(&[
+///     (ApplicationIdentifier::Batch, "ABC123".to_string()),
+///     (ApplicationIdentifier::SerialNumber, "6789".to_string()),
+/// ]);
+/// assert_eq!(data, "10ABC123\x1d216789");
+/// ```
+// GS1 General Specifications Section 5.4.7.5
+pub fn to_gs1_128(elements: &[(ApplicationIdentifier, String)]) -> String {
+    let mut result = String::new();
+    for (i, (ai, value)) in elements.iter().enumerate() {
+        let ai = ai.to_digits();
+        result.push_str(&ai);
+        result.push_str(value);
+        if !has_predefined_length(&ai) && i + 1 < elements.len() {
+            result.push(FNC1);
+        }
+    }
+    result
+}
@@ -1,14 +1,20 @@
-//! The GS1 checksum algorithm
+//! The GS1 check digit algorithms
+use crate::error::{Gs1Error, Result};
+use alloc::{string::ToString, vec::Vec};
 
-fn int_digits(input: &str) -> Vec<u16> {
+fn int_digits(input: &str) -> Result<Vec<u16>> {
     input
         .chars()
-        .map(|d| d.to_digit(10).unwrap() as u16)
+        .map(|d| d.to_digit(10).map(|d| d as u16).ok_or(Gs1Error::Parse))
         .collect()
 }
 
 /// Calculate a GS1 checksum digit.
 ///
+/// # Panics
+/// Panics if the input contains anything other than decimal digits. Use `try_gs1_checksum`
+/// for input which hasn't already been validated.
+///
 /// # Example
 /// ```
 /// # use gs1::checksum::gs1_checksum;
@@ -20,7 +26,20 @@ fn int_digits(input: &str) -> Vec<u16> {
 /// GS1 General Specifications Section 7.9.1 - a description can also be found [on the GS1
 /// website](https://www.gs1.org/services/how-calculate-check-digit-manually).
 pub fn gs1_checksum(input: &str) -> u8 {
-    let digits = int_digits(input);
+    try_gs1_checksum(input).expect("GS1 checksum input must only contain decimal digits")
+}
+
+/// Calculate a GS1 checksum digit, returning an error if the input contains anything other than
+/// decimal digits.
+///
+/// # Example
+/// ```
+/// # use gs1::checksum::try_gs1_checksum;
+/// assert_eq!(try_gs1_checksum("0360843951968").unwrap(), 0);
+/// assert!(try_gs1_checksum("03608439519A8").is_err());
+/// ```
+pub fn try_gs1_checksum(input: &str) -> Result<u8> {
+    let digits = int_digits(input)?;
     let mut even: u16 = 0;
     let mut odd: u16 = 0;
 
@@ -38,11 +57,134 @@ pub fn gs1_checksum(input: &str) -> u8 {
         check = 10 - check;
     }
 
-    check as u8
+    Ok(check as u8)
+}
+
+/// Validate a code which ends in a GS1 checksum digit, such as a GTIN or SSCC.
+///
+/// Returns false if the input is empty or contains anything other than decimal digits.
+///
+/// # Example
+/// ```
+/// # use gs1::checksum::gs1_checksum_valid;
+/// assert!(gs1_checksum_valid("5901234123457"));
+/// assert!(!gs1_checksum_valid("5901234123458"));
+/// ```
+pub fn gs1_checksum_valid(input: &str) -> bool {
+    if input.len() < 2 || !input.is_ascii() {
+        return false;
+    }
+    let (body, check) = input.split_at(input.len() - 1);
+    match try_gs1_checksum(body) {
+        Ok(digit) => digit.to_string() == check,
+        Err(_) => false,
+    }
+}
+
+// Weighted products of each digit for the price check digit, GS1 General Specifications Figure
+// 7.9.3-1. The weighting factor "2-" multiplies by 2 and subtracts the tens digit of the result
+// from its units digit, "5+" multiplies by 5 and adds them, and so on.
+const WEIGHT_2_MINUS: [u8; 10] = [0, 2, 4, 6, 8, 9, 1, 3, 5, 7];
+const WEIGHT_3: [u8; 10] = [0, 3, 6, 9, 2, 5, 8, 1, 4, 7];
+const WEIGHT_5_PLUS: [u8; 10] = [0, 5, 1, 6, 2, 7, 3, 8, 4, 9];
+const WEIGHT_5_MINUS: [u8; 10] = [0, 5, 9, 4, 8, 3, 7, 2, 6, 1];
+
+/// Calculate the check digit for a four or five-digit price field in a variable measure trade
+/// item number.
+///
+/// # Panics
+/// Panics if the input isn't four or five decimal digits. Use `try_price_check_digit` for input
+/// which hasn't already been validated.
+///
+/// # Example
+/// ```
+/// # use gs1::checksum::price_check_digit;
+/// assert_eq!(price_check_digit("2875"), 9);
+/// assert_eq!(price_check_digit("14685"), 6);
+/// ```
+///
+/// # Further Information
+/// GS1 General Specifications Section 7.9.3
+pub fn price_check_digit(input: &str) -> u8 {
+    try_price_check_digit(input).expect("price must be four or five decimal digits")
+}
+
+/// Calculate the check digit for a four or five-digit price field, returning an error if the
+/// input is any other length or contains anything other than decimal digits.
+///
+/// # Example
+/// ```
+/// # use gs1::checksum::try_price_check_digit;
+/// assert_eq!(try_price_check_digit("2875").unwrap(), 9);
+/// assert!(try_price_check_digit("287").is_err());
+/// ```
+pub fn try_price_check_digit(input: &str) -> Result<u8> {
+    let digits = int_digits(input)?;
+    let weights: &[&[u8; 10]] = match digits.len() {
+        4 => &[&WEIGHT_2_MINUS, &WEIGHT_2_MINUS, &WEIGHT_3, &WEIGHT_5_MINUS],
+        5 => &[
+            &WEIGHT_5_PLUS,
+            &WEIGHT_2_MINUS,
+            &WEIGHT_5_MINUS,
+            &WEIGHT_5_PLUS,
+            &WEIGHT_2_MINUS,
+        ],
+        _ => return Err(Gs1Error::Parse),
+    };
+
+    let sum: u16 = digits
+        .iter()
+        .zip(weights.iter())
+        .map(|(&digit, weight)| weight[digit as usize] as u16)
+        .sum();
+
+    Ok(if digits.len() == 4 {
+        // The check digit is the units digit of three times the sum
+        (sum * 3 % 10) as u8
+    } else {
+        // The check digit is the digit whose "5-" weighted product makes the sum up to a
+        // multiple of ten
+        let remainder = ((10 - sum % 10) % 10) as u8;
+        WEIGHT_5_MINUS
+            .iter()
+            .position(|&product| product == remainder)
+            .expect("the 5- weighted products cover every digit") as u8
+    })
+}
+
+#[test]
+fn test_price_check_digit() {
+    // GS1 General Specifications Section 7.9.3 worked examples
+    assert_eq!(price_check_digit("2875"), 9);
+    assert_eq!(price_check_digit("14685"), 6);
+
+    assert_eq!(price_check_digit("0000"), 0);
+    assert_eq!(price_check_digit("00000"), 0);
+    assert!(try_price_check_digit("287").is_err());
+    assert!(try_price_check_digit("146850").is_err());
+    assert!(try_price_check_digit("28a5").is_err());
 }
 
 #[test]
 fn test_gs1_checksum() {
-    assert_eq!(0, gs1_checksum(&"0360843951968".to_string()));
-    assert_eq!(8, gs1_checksum(&"8061414112345".to_string()));
+    assert_eq!(0, gs1_checksum("0360843951968"));
+    assert_eq!(8, gs1_checksum("8061414112345"));
+}
+
+#[test]
+fn test_try_gs1_checksum() {
+    assert_eq!(0, try_gs1_checksum("0360843951968").unwrap());
+    assert!(try_gs1_checksum("03608439519x8").is_err());
+    assert!(try_gs1_checksum("036084395196٨").is_err());
+}
+
+#[test]
+fn test_gs1_checksum_valid() {
+    assert!(gs1_checksum_valid("5901234123457"));
+    assert!(gs1_checksum_valid("4006381333931"));
+    assert!(gs1_checksum_valid("80614141123458"));
+    assert!(!gs1_checksum_valid("5901234123458"));
+    assert!(!gs1_checksum_valid("4006381333932"));
+    assert!(!gs1_checksum_valid("59012341234a7"));
+    assert!(!gs1_checksum_valid(""));
 }
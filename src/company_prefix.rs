@@ -0,0 +1,258 @@
+//! GS1 Company Prefix lengths
+//!
+//! The length of the GS1 Company Prefix at the start of a GTIN isn't encoded in the GTIN itself.
+//! GS1 publishes a table of prefixes and the length of the company prefixes allocated under each
+//! one, which is used here to split bare GTINs read from barcodes.
+//!
+//! The table embedded here is a subset of the GS1 Company Prefix length table, covering the
+//! prefix ranges which the GS1 General Specifications define as not containing a company prefix
+//! at all. The full table changes as GS1 Member Organisations allocate new prefixes, so it isn't
+//! embedded. Download GS1's published table (`gcpprefixformatlist.xml`) and load it with
+//! `PrefixTable::from_xml` to look up the lengths of allocated company prefixes.
+use crate::error::{Gs1Error, Result};
+use crate::util::zero_pad;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The version of the prefix table embedded in this library.
+pub const PREFIX_TABLE_VERSION: &str = "GS1 General Specifications 24.0, Figure 1.4.2-1";
+
+// What the GTINs starting with a prefix contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Allocation {
+    // A company prefix of this length
+    Company(usize),
+    // Restricted Circulation Numbers, which are only unique within a company or region
+    Restricted,
+    // No company prefix
+    NoCompany,
+}
+
+// Prefixes of the GTIN-13 form of a GTIN (with the indicator digit of a GTIN-14 removed), and
+// what the GTINs starting with them contain. Sorted by prefix, for binary search.
+const PREFIXES: [(&str, Allocation); 13] = [
+    // GTIN-8, zero-padded
+    ("00000", Allocation::NoCompany),
+    // Restricted circulation GTIN-8 (RCN-8) with the GS1-8 prefix 0 or 2
+    ("000000", Allocation::Restricted),
+    ("000002", Allocation::Restricted),
+    // Restricted circulation GTIN-12 within a company
+    ("02", Allocation::Restricted),
+    // Restricted circulation GTIN-12 within a geographic region
+    ("04", Allocation::Restricted),
+    // GS1 US coupons
+    ("05", Allocation::NoCompany),
+    // Restricted circulation GTIN-13 within a geographic region
+    ("2", Allocation::Restricted),
+    // Refund receipts
+    ("980", Allocation::NoCompany),
+    // Coupons in common currency areas
+    ("981", Allocation::NoCompany),
+    ("982", Allocation::NoCompany),
+    ("983", Allocation::NoCompany),
+    ("984", Allocation::NoCompany),
+    // Coupons
+    ("99", Allocation::NoCompany),
+];
+
+/// Return the length of the GS1 Company Prefix in a GTIN, using the prefix table.
+///
+/// The GTIN may be a GTIN-8, GTIN-12, GTIN-13, or GTIN-14, and its check digit isn't validated.
+/// Returns `None` if the GTIN is malformed, if its prefix isn't in the embedded table (see
+/// `PREFIX_TABLE_VERSION`), or if GTINs with this prefix don't contain a company prefix. As the
+/// embedded table doesn't include allocated prefixes, use `PrefixTable` to find their lengths.
+///
+/// # Example
+/// ```
+/// # use gs1::company_prefix_length;
+/// // Restricted circulation numbers don't have a company prefix
+/// assert_eq!(company_prefix_length("2012345678903"), None);
+/// ```
+// GS1 General Specifications Section 1.4.2
+pub fn company_prefix_length(gtin: &str) -> Option<usize> {
+    match lookup(&PREFIXES, gtin)? {
+        Allocation::Company(length) => Some(length),
+        Allocation::Restricted | Allocation::NoCompany => None,
+    }
+}
+
+// Whether a GTIN is a Restricted Circulation Number, using the embedded table.
+// GS1 General Specifications Section 1.4.2
+pub(crate) fn is_restricted_circulation(gtin: &str) -> bool {
+    lookup(&PREFIXES, gtin) == Some(Allocation::Restricted)
+}
+
+/// A GS1 Company Prefix length table, loaded from GS1's `gcpprefixformatlist.xml`.
+///
+/// # Example
+/// ```
+/// # use gs1::company_prefix::PrefixTable;
+/// # use gs1::GTIN;
+/// let table = PrefixTable::from_xml(
+///     r#"<GCPPrefixFormatList date="2024-01-01">
+///         <entry prefix="061" gcpLength="7"/>
+///         <entry prefix="0614" gcpLength="9"/>
+///     </GCPPrefixFormatList>"#,
+/// )
+/// .unwrap();
+/// assert_eq!(table.version(), Some("2024-01-01"));
+/// assert_eq!(table.company_prefix_length("80614141123458"), Some(9));
+///
+/// let gtin = GTIN::parse_with_prefix("80614141123458", 9).unwrap();
+/// assert_eq!(gtin.company, 61414112);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixTable {
+    version: Option<String>,
+    prefixes: Vec<(String, Allocation)>,
+}
+
+impl PrefixTable {
+    /// Load the table from the contents of GS1's `gcpprefixformatlist.xml`.
+    ///
+    /// Each `entry` element gives a `prefix` and the `gcpLength` of the company prefixes
+    /// allocated under it, where a length of 0 means that GTINs with this prefix don't contain a
+    /// company prefix. Returns `Gs1Error::Parse` if an entry is malformed or there are none.
+    pub fn from_xml(xml: &str) -> Result<PrefixTable> {
+        let mut version = None;
+        let mut prefixes = Vec::new();
+
+        for element in xml.split('<').skip(1) {
+            let element = element.split('>').next().unwrap_or_default();
+            match element.strip_prefix("entry") {
+                Some(attributes) if attributes.starts_with(char::is_whitespace) => {
+                    let prefix = attribute(attributes, "prefix").ok_or(Gs1Error::Parse)?;
+                    let length: usize = attribute(attributes, "gcpLength")
+                        .ok_or(Gs1Error::Parse)?
+                        .parse()?;
+                    if prefix.is_empty()
+                        || prefix.len() > 12
+                        || !prefix.chars().all(|c| c.is_ascii_digit())
+                        || length > 12
+                    {
+                        return Err(Gs1Error::Parse);
+                    }
+                    let allocation = match length {
+                        0 => Allocation::NoCompany,
+                        length => Allocation::Company(length),
+                    };
+                    prefixes.push((prefix.to_string(), allocation));
+                }
+                // The date of the table is an attribute of the root element
+                _ if version.is_none() => {
+                    version = attribute(element, "date").map(|date| date.to_string());
+                }
+                _ => (),
+            }
+        }
+
+        if prefixes.is_empty() {
+            return Err(Gs1Error::Parse);
+        }
+        prefixes.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(PrefixTable { version, prefixes })
+    }
+
+    /// The date of the table, from the `date` attribute of its root element.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Return the length of the GS1 Company Prefix in a GTIN, using this table.
+    ///
+    /// This behaves the same as `company_prefix_length`, apart from the table used.
+    pub fn company_prefix_length(&self, gtin: &str) -> Option<usize> {
+        match lookup(&self.prefixes, gtin)? {
+            Allocation::Company(length) => Some(length),
+            Allocation::Restricted | Allocation::NoCompany => None,
+        }
+    }
+}
+
+// Find the entry for the longest prefix of a GTIN in a sorted table.
+fn lookup<S: AsRef<str>>(prefixes: &[(S, Allocation)], gtin: &str) -> Option<Allocation> {
+    if ![8, 12, 13, 14].contains(&gtin.len()) || !gtin.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // Match against the GTIN-13 form, ignoring the indicator digit of a GTIN-14
+    let gtin13 = zero_pad(gtin[gtin.len().saturating_sub(13)..].to_string(), 13);
+
+    (1..13).rev().find_map(|length| {
+        let prefix = &gtin13[..length];
+        prefixes
+            .binary_search_by(|(entry, _)| entry.as_ref().cmp(prefix))
+            .ok()
+            .map(|i| prefixes[i].1)
+    })
+}
+
+// Return the value of an attribute of an XML element.
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    attributes.match_indices(name).find_map(|(i, _)| {
+        if !attributes[..i].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let value = attributes[i + name.len()..].strip_prefix("=\"")?;
+        value.split('"').next()
+    })
+}
+
+#[test]
+fn test_prefix_table_sorted() {
+    assert!(PREFIXES.windows(2).all(|w| w[0].0 < w[1].0));
+}
+
+#[test]
+fn test_company_prefix_length() {
+    assert_eq!(company_prefix_length("2012345678903"), None);
+    assert_eq!(company_prefix_length("02345673"), None);
+    assert_eq!(company_prefix_length("012345678905"), None);
+    assert_eq!(company_prefix_length("9912345678906"), None);
+    assert_eq!(company_prefix_length("0ABC"), None);
+}
+
+#[test]
+fn test_is_restricted_circulation() {
+    assert!(is_restricted_circulation("2012345678903"));
+    assert!(is_restricted_circulation("02345673"));
+    assert!(is_restricted_circulation("22345677"));
+    assert!(is_restricted_circulation("212345678902"));
+    assert!(!is_restricted_circulation("95012346"));
+    assert!(!is_restricted_circulation("0614141123452"));
+    assert!(!is_restricted_circulation("9912345678906"));
+}
+
+#[test]
+fn test_prefix_table() {
+    let table = PrefixTable::from_xml(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <GCPPrefixFormatList date="2024-01-01T00:00:00">
+            <entry prefix="20" gcpLength="0"/>
+            <entry gcpLength="8" prefix="40"/>
+            <entry prefix="400" gcpLength="7"/>
+            <entry prefix="0061" gcpLength="10"/>
+        </GCPPrefixFormatList>"#,
+    )
+    .unwrap();
+    assert_eq!(table.version(), Some("2024-01-01T00:00:00"));
+    assert_eq!(table.company_prefix_length("4000001000005"), Some(7));
+    assert_eq!(table.company_prefix_length("4012345000009"), Some(8));
+    assert_eq!(table.company_prefix_length("14012345000006"), Some(8));
+    assert_eq!(table.company_prefix_length("061414112345"), Some(10));
+    assert_eq!(table.company_prefix_length("2012345678903"), None);
+    assert_eq!(table.company_prefix_length("5012345678900"), None);
+
+    assert_eq!(PrefixTable::from_xml(""), Err(Gs1Error::Parse));
+    assert_eq!(
+        PrefixTable::from_xml(r#"<entry prefix="4A" gcpLength="7"/>"#),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        PrefixTable::from_xml(r#"<entry prefix="40" gcpLength="13"/>"#),
+        Err(Gs1Error::Parse)
+    );
+    assert!(PrefixTable::from_xml(r#"<entry prefix="40"/>"#).is_err());
+}
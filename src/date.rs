@@ -0,0 +1,83 @@
+//! Dates in GS1 element strings
+//!
+//! AIs such as the best before and expiry dates carry a date as `YYMMDD`. The century isn't
+//! included, so it's chosen to give the date closest to the present, and a day of `00` means the
+//! last day of the month.
+use crate::error::{Gs1Error, Result};
+use crate::ApplicationIdentifier;
+use chrono::{Datelike, NaiveDate};
+
+/// Parse the value of a date AI (11, 12, 13, 15, 16 or 17) into a date.
+///
+/// The century is determined relative to the current date, using the system clock. Use
+/// `parse_date_ai_at` to provide the current date.
+///
+/// Returns `Gs1Error::Parse` if the AI doesn't contain a date, or if the value isn't a valid
+/// `YYMMDD` date.
+#[cfg(feature = "std")]
+pub fn parse_date_ai(ai: ApplicationIdentifier, value: &str) -> Result<NaiveDate> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|_| Gs1Error::Parse)?;
+    let today = chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
+        .ok_or(Gs1Error::Parse)?
+        .date_naive();
+    parse_date_ai_at(ai, value, today)
+}
+
+/// Parse the value of a date AI (11, 12, 13, 15, 16 or 17) into a date, determining the century
+/// relative to `today`.
+///
+/// The year is the one ending in `YY` which is no more than 50 years in the past and 49 years in
+/// the future. A day of `00` means the last day of the month.
+///
+/// # Example
+/// ```
+/// # use gs1::date::parse_date_ai_at;
+/// # use gs1::ApplicationIdentifier;
+/// # use chrono::NaiveDate;
+/// let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+/// assert_eq!(
+///     parse_date_ai_at(ApplicationIdentifier::ExpirationDate, "280200", today).unwrap(),
+///     NaiveDate::from_ymd_opt(2028, 2, 29).unwrap()
+/// );
+/// ```
+// GS1 General Specifications Section 7.12
+pub fn parse_date_ai_at(
+    ai: ApplicationIdentifier,
+    value: &str,
+    today: NaiveDate,
+) -> Result<NaiveDate> {
+    use ApplicationIdentifier::*;
+    match ai {
+        ProductionDate | DueDate | PackagingDate | BestBeforeDate | SellByDate | ExpirationDate => {
+        }
+        _ => return Err(Gs1Error::Parse),
+    }
+    if !ai.spec().is_valid(value) {
+        return Err(Gs1Error::Parse);
+    }
+    let yy: i32 = value[0..2].parse()?;
+    let month: u32 = value[2..4].parse()?;
+    let day: u32 = value[4..6].parse()?;
+
+    let current_yy = today.year() % 100;
+    let century = today.year() - current_yy;
+    let year = match yy - current_yy {
+        51..=99 => century - 100 + yy,
+        -99..=-50 => century + 100 + yy,
+        _ => century + yy,
+    };
+
+    let date = match day {
+        // The day before the first of the following month
+        0 => match month {
+            12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+            _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+        }
+        .filter(|_| month > 0)
+        .and_then(|date| date.pred_opt()),
+        _ => NaiveDate::from_ymd_opt(year, month, day),
+    };
+    date.ok_or(Gs1Error::Parse)
+}
@@ -0,0 +1,459 @@
+//! GS1 Digital Link
+//!
+//! GS1 Digital Link represents GS1 identifiers as web URIs, so that they can be encoded in QR
+//! codes and resolved to online information. The compressed form packs the identifiers into a
+//! binary string, which is carried in the URI path as URI-safe base64, producing much shorter
+//! URIs than the uncompressed `/01/{gtin}/21/{serial}` form.
+//!
+//! Only the GTIN and serial number case is currently supported for compression. The optional
+//! optimisation codes for common AI combinations aren't used, as every AI can also be encoded
+//! individually.
+//!
+//! GS1 Digital Link: Compression, Section 2
+use crate::error::{Gs1Error, Result};
+use crate::util::uri_decode;
+use crate::ApplicationIdentifier;
+use alloc::{format, string::String, vec::Vec};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Parse an uncompressed GS1 Digital Link URI into its AIs and values.
+///
+/// Any domain is accepted, including the canonical `id.gs1.org`. A custom domain may have a path
+/// prefix, so the AIs are read from pairs of path segments starting at the first primary key
+/// (such as the GTIN). AIs are also read from query parameters with numeric names; other query
+/// parameters (such as `linkType`) are ignored. Values are percent-decoded, and returned in the
+/// order they appear.
+///
+/// # Example
+/// ```
+/// # use gs1::{parse_digital_link, ApplicationIdentifier};
+/// let ais = parse_digital_link("https://example.com/products/01/09506000134352/21/ABC").unwrap();
+/// assert_eq!(
+///     ais,
+///     vec![
+///         (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+///         (ApplicationIdentifier::SerialNumber, "ABC".to_string()),
+///     ]
+/// );
+/// ```
+// GS1 Digital Link Standard: URI Syntax
+pub fn parse_digital_link(uri: &str) -> Result<Vec<(ApplicationIdentifier, String)>> {
+    let (_scheme, rest) = uri.split_once("://").ok_or(Gs1Error::Parse)?;
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (_host, path) = rest.split_once('/').ok_or(Gs1Error::Parse)?;
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    // Skip the path prefix. The primary key is followed by pairs of segments to the end of the
+    // path.
+    let start = (0..segments.len())
+        .find(|&i| {
+            (segments.len() - i).is_multiple_of(2)
+                && segments[i]
+                    .parse::<ApplicationIdentifier>()
+                    .is_ok_and(|ai| PRIMARY_KEYS.iter().any(|(key, _)| *key == ai))
+        })
+        .ok_or(Gs1Error::Parse)?;
+
+    let mut ais = Vec::new();
+    for pair in segments[start..].chunks(2) {
+        ais.push((
+            pair[0].parse::<ApplicationIdentifier>()?,
+            uri_decode(pair[1])?,
+        ));
+    }
+
+    for param in query.into_iter().flat_map(|q| q.split('&')) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        if !key.is_empty() && key.chars().all(|c| c.is_ascii_digit()) {
+            ais.push((key.parse::<ApplicationIdentifier>()?, uri_decode(value)?));
+        }
+    }
+
+    Ok(ais)
+}
+
+/// Parse an uncompressed GS1 Digital Link URI, returning the values of the AIs it contains.
+///
+/// This is `parse_digital_link`, collected into a map. If an AI appears more than once, the last
+/// value is kept.
+///
+/// # Example
+/// ```
+/// # use gs1::{from_digital_link, ApplicationIdentifier};
+/// let ais = from_digital_link("https://example.com/01/09506000134352/21/ABC").unwrap();
+/// assert_eq!(ais[&ApplicationIdentifier::GTIN], "09506000134352");
+/// assert_eq!(ais[&ApplicationIdentifier::SerialNumber], "ABC");
+/// ```
+#[cfg(feature = "std")]
+pub fn from_digital_link(uri: &str) -> Result<HashMap<ApplicationIdentifier, String>> {
+    Ok(parse_digital_link(uri)?.into_iter().collect())
+}
+
+// Characters which must be percent-encoded in Digital Link path segments and query values.
+// GS1 Digital Link Standard: URI Syntax
+const DIGITAL_LINK_RESERVED: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'!')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'\'')
+    .add(b'(')
+    .add(b')')
+    .add(b'*')
+    .add(b'+')
+    .add(b',')
+    .add(b'/')
+    .add(b':')
+    .add(b';')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'?');
+
+// Primary key AIs which can start a Digital Link path, with the key qualifier AIs which may
+// follow them, in the order they must appear.
+// GS1 Digital Link Standard: URI Syntax
+const PRIMARY_KEYS: [(ApplicationIdentifier, &[ApplicationIdentifier]); 13] = {
+    use ApplicationIdentifier::*;
+    [
+        (GTIN, &[ConsumerProductVariant, Batch, SerialNumber]),
+        (ITIP, &[ConsumerProductVariant, Batch, SerialNumber]),
+        (SSCC, &[]),
+        (LocationGLN, &[GLNExtension]),
+        (PayTo, &[PaymentSlipReference]),
+        (GRAI, &[]),
+        (GIAI, &[]),
+        (GDTI, &[]),
+        (GCN, &[]),
+        (GINC, &[]),
+        (GSIN, &[]),
+        (GSRNProvider, &[ServiceRelationInstance]),
+        (GSRNRecipient, &[ServiceRelationInstance]),
+    ]
+};
+
+/// Build an uncompressed GS1 Digital Link URI from a list of AIs and their values.
+///
+/// The first AI in the list which can act as a primary key (such as the GTIN) is placed first in
+/// the path, followed by any of its key qualifiers (such as the batch and serial number) in the
+/// order required by the standard. All other AIs are added as query parameters, in the order
+/// given. `domain` should include the scheme, such as `https://id.gs1.org`.
+///
+/// Returns an error if there's no primary key, or if any value isn't valid for its AI.
+///
+/// # Example
+/// ```
+/// # use gs1::digital_link::to_digital_link;
+/// # use gs1::ApplicationIdentifier;
+/// let uri = to_digital_link(
+///     &[
+///         (ApplicationIdentifier::ExpirationDate, "251231".to_string()),
+///         (ApplicationIdentifier::SerialNumber, "ABC/1".to_string()),
+///         (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+///     ],
+///     "https://id.gs1.org",
+/// )
+/// .unwrap();
+/// assert_eq!(uri, "https://id.gs1.org/01/09506000134352/21/ABC%2F1?17=251231");
+/// ```
+pub fn to_digital_link(ais: &[(ApplicationIdentifier, String)], domain: &str) -> Result<String> {
+    for (ai, value) in ais {
+        if !ai.spec().is_valid(value) {
+            return Err(Gs1Error::Parse);
+        }
+    }
+
+    let (primary_key, qualifiers) = ais
+        .iter()
+        .find_map(|(ai, _)| PRIMARY_KEYS.iter().find(|(key, _)| key == ai))
+        .ok_or(Gs1Error::Parse)?;
+
+    let mut path: Vec<&(ApplicationIdentifier, String)> = Vec::new();
+    for path_ai in core::iter::once(primary_key).chain(qualifiers.iter()) {
+        if let Some(element) = ais.iter().find(|(ai, _)| ai == path_ai) {
+            path.push(element);
+        }
+    }
+
+    let mut uri = String::from(domain.trim_end_matches('/'));
+    for (ai, value) in &path {
+        uri.push_str(&format!(
+            "/{}/{}",
+            ai.to_digits(),
+            utf8_percent_encode(value, DIGITAL_LINK_RESERVED)
+        ));
+    }
+
+    let mut separator = '?';
+    for (ai, value) in ais {
+        if path.iter().any(|(path_ai, _)| path_ai == ai) {
+            continue;
+        }
+        uri.push_str(&format!(
+            "{}{}={}",
+            separator,
+            ai.to_digits(),
+            utf8_percent_encode(value, DIGITAL_LINK_RESERVED)
+        ));
+        separator = '&';
+    }
+
+    Ok(uri)
+}
+
+// URI-safe base64 alphabet, used both for the final URI and for serials made up only of these
+// characters.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// The maximum length of the AI (21) serial number, which determines the size of its length
+// indicator.
+const SERIAL_MAX_LENGTH: usize = 20;
+
+// Encoding indicators for variable-length alphanumeric values
+const ENCODING_NUMERIC: u8 = 0;
+const ENCODING_HEX_LOWER: u8 = 1;
+const ENCODING_HEX_UPPER: u8 = 2;
+const ENCODING_BASE64: u8 = 3;
+const ENCODING_ASCII: u8 = 4;
+
+// The number of bits needed to encode a decimal number with the given number of digits.
+fn numeric_bits(digits: usize) -> usize {
+    let limit = 10u128.pow(digits as u32);
+    let mut bits = 0;
+    while (1u128 << bits) < limit {
+        bits += 1;
+    }
+    bits
+}
+
+// The number of bits needed to encode a length between 0 and `max_length`.
+fn length_bits(max_length: usize) -> usize {
+    let mut bits = 0;
+    while (1 << bits) <= max_length {
+        bits += 1;
+    }
+    bits
+}
+
+fn write_bits(output: &mut Vec<bool>, value: u128, bits: usize) {
+    for i in (0..bits).rev() {
+        output.push((value >> i) & 1 == 1);
+    }
+}
+
+fn read_bits(input: &[bool], position: &mut usize, bits: usize) -> Result<u128> {
+    let end = *position + bits;
+    if end > input.len() {
+        return Err(Gs1Error::Parse);
+    }
+    let value = input[*position..end]
+        .iter()
+        .fold(0, |acc, &bit| (acc << 1) | bit as u128);
+    *position = end;
+    Ok(value)
+}
+
+// AIs are written as one 4-bit nibble per decimal digit.
+fn write_ai(output: &mut Vec<bool>, ai: &str) {
+    for digit in ai.chars().filter_map(|c| c.to_digit(10)) {
+        write_bits(output, digit as u128, 4);
+    }
+}
+
+fn read_ai(input: &[bool], position: &mut usize, digits: usize) -> Result<String> {
+    Ok(format!(
+        "{:0>width$x}",
+        read_bits(input, position, digits * 4)?,
+        width = digits
+    ))
+}
+
+fn write_numeric(output: &mut Vec<bool>, value: &str) -> Result<()> {
+    let number = value.parse::<u128>()?;
+    write_bits(output, number, numeric_bits(value.len()));
+    Ok(())
+}
+
+fn read_numeric(input: &[bool], position: &mut usize, digits: usize) -> Result<String> {
+    let number = read_bits(input, position, numeric_bits(digits))?;
+    if number >= 10u128.pow(digits as u32) {
+        return Err(Gs1Error::Parse);
+    }
+    Ok(format!("{:0>width$}", number, width = digits))
+}
+
+// Pick the most compact encoding for a variable-length value.
+fn variable_encoding(value: &str) -> Result<u8> {
+    Ok(if value.chars().all(|c| c.is_ascii_digit()) {
+        ENCODING_NUMERIC
+    } else if value.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+        ENCODING_HEX_LOWER
+    } else if value.chars().all(|c| matches!(c, '0'..='9' | 'A'..='F')) {
+        ENCODING_HEX_UPPER
+    } else if value.bytes().all(|c| BASE64_ALPHABET.contains(&c)) {
+        ENCODING_BASE64
+    } else if value.is_ascii() {
+        ENCODING_ASCII
+    } else {
+        return Err(Gs1Error::Parse);
+    })
+}
+
+// Variable-length values are written as a 3-bit encoding indicator, a length indicator, and
+// then the characters in the chosen encoding.
+fn write_variable(output: &mut Vec<bool>, value: &str, max_length: usize) -> Result<()> {
+    if value.is_empty() || value.len() > max_length {
+        return Err(Gs1Error::Parse);
+    }
+    let encoding = variable_encoding(value)?;
+    write_bits(output, encoding as u128, 3);
+    write_bits(output, value.len() as u128, length_bits(max_length));
+
+    match encoding {
+        ENCODING_NUMERIC => write_numeric(output, value)?,
+        ENCODING_HEX_LOWER | ENCODING_HEX_UPPER => {
+            for c in value.chars().filter_map(|c| c.to_digit(16)) {
+                write_bits(output, c as u128, 4);
+            }
+        }
+        ENCODING_BASE64 => {
+            for c in value.bytes() {
+                let index = BASE64_ALPHABET.iter().position(|&b| b == c).unwrap();
+                write_bits(output, index as u128, 6);
+            }
+        }
+        _ => {
+            for c in value.bytes() {
+                write_bits(output, c as u128, 7);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_variable(input: &[bool], position: &mut usize, max_length: usize) -> Result<String> {
+    let encoding = read_bits(input, position, 3)? as u8;
+    let length = read_bits(input, position, length_bits(max_length))? as usize;
+
+    if encoding == ENCODING_NUMERIC {
+        return read_numeric(input, position, length);
+    }
+
+    let mut value = String::new();
+    for _i in 0..length {
+        let c = match encoding {
+            ENCODING_HEX_LOWER => {
+                core::char::from_digit(read_bits(input, position, 4)? as u32, 16).unwrap()
+            }
+            ENCODING_HEX_UPPER => core::char::from_digit(read_bits(input, position, 4)? as u32, 16)
+                .unwrap()
+                .to_ascii_uppercase(),
+            ENCODING_BASE64 => BASE64_ALPHABET[read_bits(input, position, 6)? as usize] as char,
+            ENCODING_ASCII => read_bits(input, position, 7)? as u8 as char,
+            _ => return Err(Gs1Error::Parse),
+        };
+        value.push(c);
+    }
+    Ok(value)
+}
+
+// The binary string is padded with zeros to a multiple of 6 bits, and written as URI-safe
+// base64.
+fn to_base64(bits: &[bool]) -> String {
+    bits.chunks(6)
+        .map(|chunk| {
+            let value = chunk
+                .iter()
+                .chain(core::iter::repeat(&false))
+                .take(6)
+                .fold(0, |acc, &bit| (acc << 1) | bit as usize);
+            BASE64_ALPHABET[value] as char
+        })
+        .collect()
+}
+
+fn from_base64(input: &str) -> Result<Vec<bool>> {
+    let mut bits = Vec::new();
+    for c in input.bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&b| b == c)
+            .ok_or(Gs1Error::Parse)?;
+        write_bits(&mut bits, value as u128, 6);
+    }
+    Ok(bits)
+}
+
+// Compress a GTIN-14 (including check digit) and serial number into a Digital Link URI.
+pub(crate) fn compress_gtin_serial(domain: &str, gtin: &str, serial: &str) -> Result<String> {
+    let mut bits = Vec::new();
+    write_ai(&mut bits, "01");
+    write_numeric(&mut bits, gtin)?;
+    write_ai(&mut bits, "21");
+    write_variable(&mut bits, serial, SERIAL_MAX_LENGTH)?;
+
+    Ok(format!(
+        "{}/{}",
+        domain.trim_end_matches('/'),
+        to_base64(&bits)
+    ))
+}
+
+/// Decompress a compressed Digital Link URI containing a GTIN and serial number, returning the
+/// GTIN-14 (including check digit) and serial.
+///
+/// Only the final path segment of the URI is used, so any domain is accepted.
+pub fn decompress_gtin_serial(uri: &str) -> Result<(String, String)> {
+    let path = uri.rsplit('/').next().ok_or(Gs1Error::Parse)?;
+    let bits = from_base64(path)?;
+    let mut position = 0;
+
+    if read_ai(&bits, &mut position, 2)? != "01" {
+        return Err(Gs1Error::Parse);
+    }
+    let gtin = read_numeric(&bits, &mut position, 14)?;
+    if read_ai(&bits, &mut position, 2)? != "21" {
+        return Err(Gs1Error::Parse);
+    }
+    let serial = read_variable(&bits, &mut position, SERIAL_MAX_LENGTH)?;
+
+    Ok((gtin, serial))
+}
+
+#[test]
+fn test_compress_gtin_serial() {
+    let examples = [
+        ("80614141123458", "6789"),
+        ("80614141123458", "06789"),
+        ("70614141123451", "32a/b"),
+        ("09506000134352", "abc123"),
+        ("09506000134352", "ABC123"),
+        ("09506000134352", "ABCxyz-_"),
+    ];
+    for (gtin, serial) in examples.iter() {
+        let uri = compress_gtin_serial("https://id.gs1.org/", gtin, serial).unwrap();
+        assert!(uri.starts_with("https://id.gs1.org/"));
+        assert_eq!(
+            decompress_gtin_serial(&uri).unwrap(),
+            (gtin.to_string(), serial.to_string())
+        );
+    }
+
+    // 8 bits of AI, 47 bits of GTIN, 8 bits of AI, 3 + 5 bits of header, 14 bits of serial,
+    // padded to 90 bits
+    let uri = compress_gtin_serial("https://id.gs1.org", "80614141123458", "6789").unwrap();
+    assert_eq!(uri.len(), "https://id.gs1.org/".len() + 15);
+
+    assert!(compress_gtin_serial("https://id.gs1.org", "80614141123458", "").is_err());
+    assert!(compress_gtin_serial("https://id.gs1.org", "80614141123458", "é").is_err());
+    assert!(decompress_gtin_serial("https://id.gs1.org/01/80614141123458").is_err());
+}
@@ -0,0 +1,76 @@
+//! GS1 element strings
+//!
+//! An element string is a sequence of Application Identifiers and their values, such as a GTIN
+//! followed by its batch, expiry date and serial number.
+use crate::barcode::to_gs1_128;
+use crate::error::{Gs1Error, Result};
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{format, string::String, vec::Vec};
+
+/// Builds an element string from several AIs and their values.
+///
+/// Each value is checked against the format of its AI as it's added, so the result is always
+/// valid. Elements are kept in the order they're added.
+///
+/// # Example
+/// ```
+/// # use gs1::{ApplicationIdentifier, ElementStringBuilder, GS1, FNC1};
+/// let mut builder = ElementStringBuilder::new();
+/// builder
+///     .add(ApplicationIdentifier::GTIN, "80614141123458")?
+///     .add(ApplicationIdentifier::Batch, "ABC123")?
+///     .add(ApplicationIdentifier::SerialNumber, "6789")?;
+/// assert_eq!(builder.build(), format!("018061414112345810ABC123{}216789", FNC1));
+/// assert_eq!(builder.to_gs1(), "(01) 80614141123458 (10) ABC123 (21) 6789");
+/// # Ok::<(), gs1::error::Gs1Error>(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ElementStringBuilder {
+    elements: Vec<(ApplicationIdentifier, String)>,
+}
+
+impl ElementStringBuilder {
+    /// Create an empty builder.
+    pub fn new() -> ElementStringBuilder {
+        ElementStringBuilder::default()
+    }
+
+    /// Add an AI and its value.
+    ///
+    /// Returns `Gs1Error::Parse` if the value isn't valid for the AI, as given by
+    /// `ApplicationIdentifier::spec`, or if the AI has already been added.
+    pub fn add(
+        &mut self,
+        ai: ApplicationIdentifier,
+        value: &str,
+    ) -> Result<&mut ElementStringBuilder> {
+        if !ai.spec().is_valid(value) || self.elements.iter().any(|(existing, _)| *existing == ai) {
+            return Err(Gs1Error::Parse);
+        }
+        self.elements.push((ai, String::from(value)));
+        Ok(self)
+    }
+
+    /// Return the AIs and values which have been added.
+    pub fn elements(&self) -> &[(ApplicationIdentifier, String)] {
+        &self.elements
+    }
+
+    /// Return the GS1-128 data string, with `FNC1` after each variable-length element except
+    /// the last.
+    pub fn build(&self) -> String {
+        to_gs1_128(&self.elements)
+    }
+}
+
+/// The human-readable form of the element string, such as `(01) 80614141123458 (21) 6789`.
+impl GS1 for ElementStringBuilder {
+    fn to_gs1(&self) -> String {
+        let elements: Vec<String> = self
+            .elements
+            .iter()
+            .map(|(ai, value)| format!("({}) {}", ai.to_digits(), value))
+            .collect();
+        elements.join(" ")
+    }
+}
@@ -0,0 +1,108 @@
+//! Aerospace and Defense Identifier
+//!
+//! This is a combination of a CAGE code or DoDAAC, which identifies the organisation which
+//! assigned the identifier, an optional original part number, and a serial number. It's defined
+//! by the US Department of Defense and the ATA Spec 2000 for aircraft parts.
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use crate::util::{invalid_field, read_terminated_string_6bit, uri_encode};
+use alloc::{boxed::Box, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// Variable-length Aerospace and Defense Identifier
+///
+/// This comprises a filter value, a CAGE code or DoDAAC, an alphanumeric part number, and an
+/// alphanumeric serial number.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ADIVar {
+    /// Filter
+    pub filter: u8,
+    /// CAGE code (5 characters) or DoDAAC (6 characters)
+    pub cage_or_dodaac: String,
+    /// Original part number, which may be empty
+    pub part_number: String,
+    /// Serial number. A leading `#` indicates that it's unique within the CAGE code or DoDAAC,
+    /// rather than within the part number.
+    pub serial: String,
+}
+
+impl ADIVar {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
+            "{}.{}.{}",
+            self.cage_or_dodaac,
+            uri_encode(&self.part_number),
+            uri_encode(&self.serial)
+        )
+    }
+}
+
+impl EPC for ADIVar {
+    // GS1 EPC TDS section 6.3.18
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:adi:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:adi-var:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        // Header, filter, CAGE/DoDAAC, and the two strings with their terminators
+        8 + 6 + 36 + 6 * (self.part_number.len() + 1) + 6 * (self.serial.len() + 1)
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::ADIVar(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::ADIVar(*self)
+    }
+}
+
+// GS1 EPC TDS Section 14.6.7
+pub(super) fn decode_adivar(data: &[u8]) -> Result<ADIVar> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(6)?;
+
+    // The CAGE code or DoDAAC is six 6-bit characters. A five-character CAGE code is prefixed
+    // with a space.
+    let mut cage_or_dodaac = String::with_capacity(6);
+    for i in 0..6 {
+        let value = reader.read_u8(6)?;
+        // Values below 0x20 encode '@' and A-Z, and the rest encode their ASCII value
+        let c = if value < 0x20 { value | 0x40 } else { value } as char;
+        match c {
+            ' ' if i == 0 => continue,
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => (),
+            c => return Err(Gs1Error::InvalidCharacter(c)),
+        }
+        cage_or_dodaac.push(c);
+    }
+
+    let part_number = read_terminated_string_6bit(&mut reader, 32, "part_number")?;
+    let position = reader.position();
+    let serial = read_terminated_string_6bit(&mut reader, 30, "serial")?;
+
+    // '#' may only appear as the first character of the serial number
+    if part_number.contains('#') || serial.chars().skip(1).any(|c| c == '#') {
+        return Err(Gs1Error::InvalidCharacter('#'));
+    }
+    if serial.is_empty() {
+        return Err(invalid_field("serial", position));
+    }
+
+    Ok(ADIVar {
+        filter,
+        cage_or_dodaac,
+        part_number,
+        serial,
+    })
+}
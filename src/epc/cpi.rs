@@ -0,0 +1,182 @@
+//! Component / Part Identifier
+//!
+//! This is a combination of a company prefix assigned by GS1, a component or part reference
+//! assigned by that company, and a serial number which allows an individual component to be
+//! uniquely identified.
+use crate::epc::partition::{CPI96 as PARTITIONS, CPIVAR as VAR_PARTITIONS};
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::util::{invalid_field, read_digits, read_terminated_string_6bit, uri_encode};
+use alloc::{boxed::Box, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Component / Part Identifier
+///
+/// This comprises a company prefix, a numeric component/part reference, and a numeric serial
+/// number.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CPI96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Component/part reference
+    pub part_reference: u64,
+    /// Serial number
+    pub serial: u32,
+}
+
+impl CPI96 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, _) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{}.{}",
+            self.company_prefix, self.part_reference, self.serial
+        )
+    }
+}
+
+impl EPC for CPI96 {
+    // GS1 EPC TDS section 6.3.9
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:cpi:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:cpi-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::CPI96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::CPI96(*self)
+    }
+}
+
+/// Variable-length Component / Part Identifier
+///
+/// This comprises a company prefix, an alphanumeric component/part reference, and a numeric
+/// serial number.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CPIVar {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Alphanumeric component/part reference
+    pub part_reference: String,
+    /// Serial number, of at most 12 digits
+    pub serial: u64,
+}
+
+impl CPIVar {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, _) = VAR_PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{}.{}",
+            self.company_prefix,
+            uri_encode(&self.part_reference),
+            self.serial
+        )
+    }
+}
+
+impl EPC for CPIVar {
+    // GS1 EPC TDS section 6.3.9
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:cpi:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:cpi-var:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        let company_bits = VAR_PARTITIONS
+            .lookup(self.partition)
+            .map_or(0, |(company_bits, _, _, _)| company_bits as usize);
+        // Header, filter, partition, company prefix, the terminated reference, and the serial
+        8 + 3 + 3 + company_bits + 6 * (self.part_reference.len() + 1) + 40
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::CPIVar(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::CPIVar(*self)
+    }
+}
+
+// GS1 EPC TDS Section 14.6.9
+pub(super) fn decode_cpi96(data: &[u8]) -> Result<CPI96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, part_reference_bits, part_reference_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let part_reference = read_digits(
+        &mut reader,
+        part_reference_bits,
+        part_reference_digits,
+        "part_reference",
+    )?;
+    let serial = reader.read_u32(31)?;
+
+    Ok(CPI96 {
+        filter,
+        partition,
+        company_prefix,
+        part_reference,
+        serial,
+    })
+}
+
+// GS1 EPC TDS Section 14.6.8
+pub(super) fn decode_cpivar(data: &[u8]) -> Result<CPIVar> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, _, part_reference_chars) =
+        VAR_PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let part_reference =
+        read_terminated_string_6bit(&mut reader, part_reference_chars as u64, "part_reference")?;
+    let position = reader.position();
+    let serial = reader.read_u64(40)?;
+
+    // The serial has at most 12 digits, which doesn't fill the 40-bit field
+    if serial >= 10u64.pow(12) {
+        return Err(invalid_field("serial", position));
+    }
+
+    Ok(CPIVar {
+        filter,
+        partition,
+        company_prefix,
+        part_reference,
+        serial,
+    })
+}
@@ -0,0 +1,237 @@
+//! Global Document Type Identifier
+//!
+//! This is a combination of a company prefix assigned by GS1, a document type assigned by that
+//! company, and a serial number which allows a document to be uniquely identified.
+use crate::checksum::gs1_checksum;
+use crate::epc::partition::GDTI as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::util::{optional_field, read_digits, read_string, uri_encode};
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Global Document Type Identifier
+///
+/// This comprises a company prefix, a document type, and a numeric serial number.
+///
+/// The serial number is optional, and a serial number of 0 indicates that there isn't one, so
+/// it's omitted from both the EPC URIs and `to_gs1`. For example, a GDTI-96 with no serial has
+/// the URI `urn:epc:id:gdti:9521141.12345`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GDTI96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Document type
+    pub document_type: u64,
+    /// Serial number
+    pub serial: u64,
+}
+
+impl GDTI96 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, document_type_width) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{:0document_type_width$}",
+            self.company_prefix, self.document_type
+        )?;
+        if let Some(serial) = optional_field(self.serial) {
+            write!(w, ".{}", serial)?;
+        }
+        Ok(())
+    }
+}
+
+impl EPC for GDTI96 {
+    // GS1 EPC TDS section 6.3.7
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:gdti:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:gdti-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::GDTI96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GDTI96(*self)
+    }
+}
+
+impl GS1 for GDTI96 {
+    fn to_gs1(&self) -> String {
+        gdti_element_string(
+            self.partition,
+            self.company_prefix,
+            self.document_type,
+            &optional_field(self.serial).map_or(String::new(), |serial| serial.to_string()),
+        )
+    }
+}
+
+/// 174-bit Global Document Type Identifier
+///
+/// This comprises a company prefix, a document type, and an alphanumeric serial number of up to
+/// 17 characters.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GDTI174 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Document type
+    pub document_type: u64,
+    /// Alphanumeric serial number
+    pub serial: String,
+}
+
+impl GDTI174 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, document_type_width) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{:0document_type_width$}.{}",
+            self.company_prefix,
+            self.document_type,
+            uri_encode(&self.serial)
+        )
+    }
+}
+
+impl EPC for GDTI174 {
+    // GS1 EPC TDS section 6.3.7
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:gdti:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:gdti-174:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        174
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::GDTI174(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GDTI174(*self)
+    }
+}
+
+impl GS1 for GDTI174 {
+    fn to_gs1(&self) -> String {
+        gdti_element_string(
+            self.partition,
+            self.company_prefix,
+            self.document_type,
+            &self.serial,
+        )
+    }
+}
+
+fn gdti_element_string(
+    partition: u8,
+    company_prefix: u64,
+    document_type: u64,
+    serial: &str,
+) -> String {
+    let (company_width, document_type_width) = PARTITIONS.digits(partition);
+    let element_string = format!(
+        "{:0company_width$}{:0document_type_width$}",
+        company_prefix, document_type
+    );
+    format!(
+        "({}) {}{}{}",
+        ApplicationIdentifier::GDTI as u16,
+        element_string,
+        gs1_checksum(&element_string),
+        serial
+    )
+}
+
+// Read the partition, company prefix, and document type fields which are common to the
+// GDTI-96 and GDTI-174 schemes.
+fn read_gdti_fields(reader: &mut BitReader) -> Result<(u8, u64, u64)> {
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, document_type_bits, document_type_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(reader, company_bits, company_digits, "company_prefix")?;
+    let document_type = read_digits(
+        reader,
+        document_type_bits,
+        document_type_digits,
+        "document_type",
+    )?;
+    Ok((partition, company_prefix, document_type))
+}
+
+// GS1 EPC TDS Section 14.5.7
+pub(super) fn decode_gdti96(data: &[u8]) -> Result<GDTI96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let (partition, company_prefix, document_type) = read_gdti_fields(&mut reader)?;
+    let serial = reader.read_u64(41)?;
+
+    Ok(GDTI96 {
+        filter,
+        partition,
+        company_prefix,
+        document_type,
+        serial,
+    })
+}
+
+// Check the fields of a GDTI-174 without decoding it. Any 7-bit characters are accepted in the
+// serial, so it doesn't need to be read.
+pub(super) fn validate_gdti174(data: &[u8]) -> Result<()> {
+    let mut reader = BitReader::new(data);
+    reader.skip(3)?;
+    read_gdti_fields(&mut reader)?;
+    Ok(())
+}
+
+// GS1 EPC TDS Section 14.5.7
+pub(super) fn decode_gdti174(data: &[u8]) -> Result<GDTI174> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let (partition, company_prefix, document_type) = read_gdti_fields(&mut reader)?;
+    let serial = read_string(reader, 119)?;
+
+    Ok(GDTI174 {
+        filter,
+        partition,
+        company_prefix,
+        document_type,
+        serial,
+    })
+}
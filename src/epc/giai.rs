@@ -0,0 +1,94 @@
+//! Global Individual Asset Identifier
+//!
+//! This is a combination of a company prefix assigned by GS1 and an individual asset reference
+//! assigned by that company, which allows an asset to be uniquely identified.
+use crate::epc::partition::GIAI96 as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::util::read_digits;
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{boxed::Box, format, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Global Individual Asset Identifier
+///
+/// This comprises a company prefix and a numeric individual asset reference.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GIAI96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Individual asset reference
+    pub asset_reference: u64,
+}
+
+impl GIAI96 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, _) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{}",
+            self.company_prefix, self.asset_reference
+        )
+    }
+}
+
+impl EPC for GIAI96 {
+    // GS1 EPC TDS section 6.3.5
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:giai:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:giai-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::GIAI96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GIAI96(*self)
+    }
+}
+
+impl GS1 for GIAI96 {
+    fn to_gs1(&self) -> String {
+        let (company_width, _) = PARTITIONS.digits(self.partition);
+        format!(
+            "({}) {:0company_width$}{}",
+            ApplicationIdentifier::GIAI as u16,
+            self.company_prefix,
+            self.asset_reference
+        )
+    }
+}
+
+// GS1 EPC TDS Section 14.5.5
+pub(super) fn decode_giai96(data: &[u8]) -> Result<GIAI96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, asset_bits, asset_digits) = PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let asset_reference = read_digits(&mut reader, asset_bits, asset_digits, "asset_reference")?;
+
+    Ok(GIAI96 {
+        filter,
+        partition,
+        company_prefix,
+        asset_reference,
+    })
+}
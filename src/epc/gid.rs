@@ -3,56 +3,108 @@
 //! This is a combination of manager number assigned by GS1, an object class
 //! assigned by that mananger, and a serial number which allows an item to
 //! be uniquely identfied.
-use crate::epc::{EPCValue, EPC};
-use crate::error::Result;
+use crate::epc::{EPCBinaryHeader, EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use crate::util::{parse_decimal, BitWriter};
+use alloc::{boxed::Box, vec::Vec};
 use bitreader::BitReader;
+use core::fmt;
 
 /// 96-bit General Identifier
 ///
 /// This comprises a manager number, an object class, and a numeric serial
 /// number.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GID96 {
-    /// General Manager Number
+    /// General Manager Number, which must be less than 2^28
     pub manager: u32,
-    /// Object Class
+    /// Object Class, which must be less than 2^24
     pub class: u32,
-    /// Item serial number
+    /// Item serial number, which must be less than 2^36
     pub serial: u64,
 }
 
 impl EPC for GID96 {
     // GS1 EPC TDS section 6.3.16
-    fn to_uri(&self) -> String {
-        format!(
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
             "urn:epc:id:gid:{}.{}.{}",
             self.manager, self.class, self.serial
         )
     }
 
-    fn to_tag_uri(&self) -> String {
-        format!(
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
             "urn:epc:tag:gid-96:{}.{}.{}",
             self.manager, self.class, self.serial
         )
     }
 
-    fn get_value(&self) -> EPCValue {
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::GID96(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GID96(*self)
+    }
 }
 
 // GS1 EPC TDS Section 14.6.12
-pub(super) fn decode_gid96(data: &[u8]) -> Result<Box<dyn EPC>> {
+pub(super) fn decode_gid96(data: &[u8]) -> Result<GID96> {
     let mut reader = BitReader::new(data);
 
     let manager = reader.read_u32(28)?;
     let class = reader.read_u32(24)?;
     let serial = reader.read_u64(36)?;
 
-    Ok(Box::new(GID96 {
+    Ok(GID96 {
         manager,
         class,
         serial,
-    }))
+    })
+}
+
+/// Encode a GID-96 to its binary form.
+///
+/// The manager number must fit in 28 bits, the object class in 24 bits, and the serial number in
+/// 36 bits.
+///
+/// Reference: GS1 EPC TDS Section 14.6.12
+pub fn encode_gid96(gid: &GID96) -> Result<Vec<u8>> {
+    if gid.manager >= 1 << 28 {
+        return Err(Gs1Error::OutOfRange("manager"));
+    }
+    if gid.class >= 1 << 24 {
+        return Err(Gs1Error::OutOfRange("class"));
+    }
+    if gid.serial >= 1 << 36 {
+        return Err(Gs1Error::OutOfRange("serial"));
+    }
+
+    let mut writer = BitWriter::new();
+    writer.write_u64(EPCBinaryHeader::GID96 as u64, 8);
+    writer.write_u64(gid.manager as u64, 28);
+    writer.write_u64(gid.class as u64, 24);
+    writer.write_u64(gid.serial, 36);
+    Ok(writer.into_bytes())
+}
+
+// The GID-96 tag URI has the same fields as the pure identity URI.
+// GS1 EPC TDS Sections 6.3.16 and 12.4
+pub(super) fn gid96_from_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [manager, class, serial] => Ok(Box::new(GID96 {
+            manager: parse_decimal::<u32>(manager)?,
+            class: parse_decimal::<u32>(class)?,
+            serial: parse_decimal::<u64>(serial)?,
+        })),
+        _ => Err(Gs1Error::Parse),
+    }
 }
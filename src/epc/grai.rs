@@ -3,97 +3,23 @@
 //! This is a combination of a company prefix assigned by GS1, an asset type
 //! assigned by that company, and a serial number which allows an item to
 //! be uniquely identified.
-use crate::epc::{EPCValue, EPC};
-use crate::error::Result;
+use crate::checksum::gs1_checksum;
+use crate::epc::partition::GRAI as PARTITIONS;
+use crate::epc::{EPCBinaryHeader, EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use crate::util::{
+    parse_decimal, parse_filter, parse_serial, read_digits, read_string, uri_encode, zero_pad,
+    BitWriter,
+};
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use bitreader::BitReader;
-
-/// Metadata for a partition
-#[derive(Debug, PartialEq)]
-#[allow(dead_code)]
-struct Partition {
-    bits: u8,
-    digits: u8,
-}
-
-/// Decoded partition metadata
-#[derive(Debug, PartialEq)]
-struct GraiPartition {
-    company_prefix: Partition,
-    asset_type: Partition,
-}
-
-/// Get the number of bits and digits for the company prefix and the asset type based on the partition value.
-///
-/// Decoded according to Table 14-14 "GRAI Partition Table" in the GS1 EPC Tag Data Standard v2.1
-fn decode_partition_value(partition_value: u8) -> Result<GraiPartition> {
-    match partition_value {
-        0 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 40,
-                digits: 12,
-            },
-            asset_type: Partition { bits: 4, digits: 0 },
-        }),
-        1 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 37,
-                digits: 11,
-            },
-            asset_type: Partition { bits: 7, digits: 1 },
-        }),
-        2 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 34,
-                digits: 10,
-            },
-            asset_type: Partition {
-                bits: 10,
-                digits: 2,
-            },
-        }),
-        3 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 30,
-                digits: 9,
-            },
-            asset_type: Partition {
-                bits: 14,
-                digits: 3,
-            },
-        }),
-        4 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 27,
-                digits: 8,
-            },
-            asset_type: Partition {
-                bits: 17,
-                digits: 4,
-            },
-        }),
-        5 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 24,
-                digits: 7,
-            },
-            asset_type: Partition {
-                bits: 20,
-                digits: 5,
-            },
-        }),
-        6 => Ok(GraiPartition {
-            company_prefix: Partition {
-                bits: 20,
-                digits: 6,
-            },
-            asset_type: Partition {
-                bits: 24,
-                digits: 6,
-            },
-        }),
-        _ => Err("Invalid partition value".into()),
-    }
-}
+use core::fmt;
 
 // EPC Header Filter Partition GS1
 // Company
@@ -104,7 +30,8 @@ fn decode_partition_value(partition_value: u8) -> Result<GraiPartition> {
 ///
 /// This comprises a manager number, an object class, and a numeric serial
 /// number.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GRAI96 {
     /// Filter
     pub filter: u8,
@@ -118,45 +45,362 @@ pub struct GRAI96 {
     pub serial: u64,
 }
 
+// Write the company prefix, asset type and serial URI fields which are common to both GRAI
+// schemes. The company prefix and asset type are zero-padded to the widths given by the
+// partition, as the leading zeros are significant.
+fn write_grai_fields(
+    w: &mut dyn fmt::Write,
+    partition: u8,
+    company_prefix: u64,
+    asset_type: u32,
+    serial: &dyn fmt::Display,
+) -> fmt::Result {
+    let (company_width, asset_type_width) = PARTITIONS.digits(partition);
+    write!(
+        w,
+        "{:0company_width$}.{:0asset_type_width$}.{}",
+        company_prefix, asset_type, serial
+    )
+}
+
 impl EPC for GRAI96 {
     // GS1 EPC TDS section 14.6.4
-    fn to_uri(&self) -> String {
-        format!(
-            "urn:epc:id:grai:{}.{}.{}",
-            self.company_prefix, self.asset_type, self.serial
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:grai:")?;
+        write_grai_fields(
+            w,
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial,
         )
     }
 
-    fn to_tag_uri(&self) -> String {
-        format!(
-            "urn:epc:tag:grai-96:{}.{}.{}.{}",
-            self.filter, self.company_prefix, self.asset_type, self.serial
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:grai-96:{}.", self.filter)?;
+        write_grai_fields(
+            w,
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial,
         )
     }
 
-    fn get_value(&self) -> EPCValue {
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::GRAI96(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GRAI96(*self)
+    }
+}
+
+impl GRAI96 {
+    /// Return the GRAI as it appears in the data field of AI (8003), including the check digit.
+    ///
+    /// Returns `Gs1Error::InvalidPartition` if the partition value is invalid, and
+    /// `Gs1Error::OutOfRange` if a field doesn't fit in the number of digits the partition allows.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::grai::GRAI96;
+    /// let grai = GRAI96 {
+    ///     filter: 3,
+    ///     partition: 5,
+    ///     company_prefix: 9521141,
+    ///     asset_type: 12345,
+    ///     serial: 5678,
+    /// };
+    /// assert_eq!(grai.to_grai().unwrap(), "095211411234545678");
+    /// ```
+    pub fn to_grai(&self) -> Result<String> {
+        grai_value(
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial.to_string(),
+        )
+    }
+}
+
+/// The element string is only valid if the partition value is. Use `to_grai` to check it.
+impl GS1 for GRAI96 {
+    fn to_gs1(&self) -> String {
+        grai_element_string(
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial.to_string(),
+        )
+    }
+}
+
+/// 170-bit Global Returnable Asset Identifier
+///
+/// This comprises a company prefix, an asset type, and an alphanumeric serial number of up to
+/// 16 characters.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GRAI170 {
+    /// Filter
+    pub filter: u8,
+    /// Partition
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Asset type
+    pub asset_type: u32,
+    /// Alphanumeric serial number
+    pub serial: String,
+}
+
+impl EPC for GRAI170 {
+    // GS1 EPC TDS section 14.6.4
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:grai:")?;
+        write_grai_fields(
+            w,
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &uri_encode(&self.serial),
+        )
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:grai-170:{}.", self.filter)?;
+        write_grai_fields(
+            w,
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &uri_encode(&self.serial),
+        )
+    }
+
+    fn bit_length(&self) -> usize {
+        170
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::GRAI170(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GRAI170(*self)
+    }
+}
+
+impl GRAI170 {
+    /// Return the GRAI as it appears in the data field of AI (8003), including the check digit.
+    ///
+    /// Returns `Gs1Error::InvalidPartition` if the partition value is invalid, and
+    /// `Gs1Error::OutOfRange` if a field doesn't fit in the number of digits the partition allows.
+    pub fn to_grai(&self) -> Result<String> {
+        grai_value(
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial,
+        )
+    }
+}
+
+/// The element string is only valid if the partition value is. Use `to_grai` to check it.
+impl GS1 for GRAI170 {
+    fn to_gs1(&self) -> String {
+        grai_element_string(
+            self.partition,
+            self.company_prefix,
+            self.asset_type,
+            &self.serial,
+        )
+    }
+}
+
+// The data field of AI (8003), checking that the fields fit the partition.
+fn grai_value(partition: u8, company_prefix: u64, asset_type: u32, serial: &str) -> Result<String> {
+    let (_, company_digits, _, asset_type_digits) = PARTITIONS.lookup(partition)?;
+    if company_prefix >= 10u64.pow(company_digits as u32) {
+        return Err(Gs1Error::OutOfRange("company_prefix"));
+    }
+    if asset_type as u64 >= 10u64.pow(asset_type_digits as u32) {
+        return Err(Gs1Error::OutOfRange("asset_type"));
+    }
+    Ok(grai_digits(partition, company_prefix, asset_type, serial))
+}
+
+fn grai_element_string(
+    partition: u8,
+    company_prefix: u64,
+    asset_type: u32,
+    serial: &str,
+) -> String {
+    format!(
+        "({}) {}",
+        ApplicationIdentifier::GRAI as u16,
+        grai_digits(partition, company_prefix, asset_type, serial)
+    )
+}
+
+fn grai_digits(partition: u8, company_prefix: u64, asset_type: u32, serial: &str) -> String {
+    let (company_digits, asset_type_digits) = PARTITIONS.digits(partition);
+    // The GRAI element string starts with a zero digit
+    let element_string = format!(
+        "0{}{}",
+        zero_pad(company_prefix.to_string(), company_digits),
+        zero_pad(asset_type.to_string(), asset_type_digits)
+    );
+    format!(
+        "{}{}{}",
+        element_string,
+        gs1_checksum(&element_string),
+        serial
+    )
 }
 
 // GS1 EPC TDS Section 14.6.4
+// Read the partition, company prefix, and asset type fields which are common to the GRAI-96
+// and GRAI-170 schemes.
+fn read_grai_fields(reader: &mut BitReader) -> Result<(u8, u64, u32)> {
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, asset_type_bits, asset_type_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(reader, company_bits, company_digits, "company_prefix")?;
+    let asset_type = read_digits(reader, asset_type_bits, asset_type_digits, "asset_type")?;
+    Ok((partition, company_prefix, asset_type as u32))
+}
+
 pub fn decode_grai96(data: &[u8]) -> Result<Box<dyn EPC>> {
+    Ok(Box::new(decode_grai96_typed(data)?))
+}
+
+pub(super) fn decode_grai96_typed(data: &[u8]) -> Result<GRAI96> {
     let mut reader = BitReader::new(data);
 
     let filter = reader.read_u8(3)?;
-    let partition = reader.read_u8(3)?;
+    let (partition, company_prefix, asset_type) = read_grai_fields(&mut reader)?;
+    let serial = reader.read_u64(38)?;
 
-    let grai_partition = decode_partition_value(partition)?;
+    Ok(GRAI96 {
+        filter,
+        partition,
+        company_prefix,
+        asset_type,
+        serial,
+    })
+}
 
-    let company_prefix = reader.read_u64(grai_partition.company_prefix.bits)?;
-    let asset_type = reader.read_u32(grai_partition.asset_type.bits)?;
-    let serial = reader.read_u64(38)?;
+/// Encode a GRAI-96 to its binary form.
+///
+/// The company prefix and asset type must fit in the number of digits given by the partition,
+/// and the serial number must fit in 38 bits.
+///
+/// Reference: GS1 EPC TDS Section 14.6.4
+pub fn encode_grai96(grai: &GRAI96) -> Result<Vec<u8>> {
+    if grai.filter > 7 {
+        return Err(Gs1Error::OutOfRange("filter"));
+    }
+    let (company_bits, company_digits, asset_type_bits, asset_type_digits) =
+        PARTITIONS.lookup(grai.partition)?;
+    if grai.company_prefix >= 10u64.pow(company_digits as u32) {
+        return Err(Gs1Error::OutOfRange("company_prefix"));
+    }
+    if grai.asset_type as u64 >= 10u64.pow(asset_type_digits as u32) {
+        return Err(Gs1Error::OutOfRange("asset_type"));
+    }
+    if grai.serial >= 1 << 38 {
+        return Err(Gs1Error::OutOfRange("serial"));
+    }
+
+    let mut writer = BitWriter::new();
+    writer.write_u64(EPCBinaryHeader::GRAI96 as u64, 8);
+    writer.write_u64(grai.filter as u64, 3);
+    writer.write_u64(grai.partition as u64, 3);
+    writer.write_u64(grai.company_prefix, company_bits);
+    writer.write_u64(grai.asset_type as u64, asset_type_bits);
+    writer.write_u64(grai.serial, 38);
+    Ok(writer.into_bytes())
+}
+
+// Check the fields of a GRAI-170 without decoding it. Any 7-bit characters are accepted in the
+// serial, so it doesn't need to be read.
+pub(super) fn validate_grai170(data: &[u8]) -> Result<()> {
+    let mut reader = BitReader::new(data);
+    reader.skip(3)?;
+    read_grai_fields(&mut reader)?;
+    Ok(())
+}
+
+// GS1 EPC TDS Section 14.6.4
+pub(super) fn decode_grai170(data: &[u8]) -> Result<GRAI170> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let (partition, company_prefix, asset_type) = read_grai_fields(&mut reader)?;
+    let serial = read_string(reader, 112)?;
 
-    Ok(Box::new(GRAI96 {
+    Ok(GRAI170 {
         filter,
         partition,
         company_prefix,
         asset_type,
         serial,
-    }))
+    })
+}
+
+fn parse_grai96(
+    filter: u8,
+    company_prefix: &str,
+    asset_type: &str,
+    serial: &str,
+) -> Result<GRAI96> {
+    if company_prefix.len() + asset_type.len() != 12 {
+        return Err(Gs1Error::Parse);
+    }
+    let partition = PARTITIONS
+        .partition(company_prefix.len())
+        .map_err(|_| Gs1Error::Parse)?;
+
+    Ok(GRAI96 {
+        filter,
+        partition,
+        company_prefix: parse_decimal::<u64>(company_prefix)?,
+        asset_type: match asset_type {
+            "" => 0,
+            asset_type => parse_decimal::<u32>(asset_type)?,
+        },
+        serial: parse_serial(serial, 38)?,
+    })
+}
+
+// GS1 EPC TDS Section 12.4
+pub(super) fn grai96_from_tag_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [filter, company_prefix, asset_type, serial] => Ok(Box::new(parse_grai96(
+            parse_filter(filter)?,
+            company_prefix,
+            asset_type,
+            serial,
+        )?)),
+        _ => Err(Gs1Error::Parse),
+    }
+}
+
+// GS1 EPC TDS Section 6.3.4
+pub(super) fn grai_from_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [company_prefix, asset_type, serial] => Ok(Box::new(parse_grai96(
+            0,
+            company_prefix,
+            asset_type,
+            serial,
+        )?)),
+        _ => Err(Gs1Error::Parse),
+    }
 }
@@ -0,0 +1,105 @@
+//! Global Service Relation Number
+//!
+//! This is a combination of a company prefix assigned by GS1 and a service reference assigned by
+//! that company, which identifies the recipient or provider of a service.
+use crate::checksum::gs1_checksum;
+use crate::epc::partition::GSRN as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::util::read_digits;
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{boxed::Box, format, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Global Service Relation Number
+///
+/// This comprises a company prefix and a service reference.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GSRN96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Service reference
+    pub service_reference: u64,
+}
+
+impl GSRN96 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, service_reference_width) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{:0service_reference_width$}",
+            self.company_prefix, self.service_reference
+        )
+    }
+}
+
+impl EPC for GSRN96 {
+    // GS1 EPC TDS section 6.3.6
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:gsrn:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:gsrn-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::GSRN96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::GSRN96(*self)
+    }
+}
+
+impl GS1 for GSRN96 {
+    fn to_gs1(&self) -> String {
+        let (company_width, service_reference_width) = PARTITIONS.digits(self.partition);
+        let element_string = format!(
+            "{:0company_width$}{:0service_reference_width$}",
+            self.company_prefix, self.service_reference
+        );
+        format!(
+            "({}) {}{}",
+            ApplicationIdentifier::GSRNRecipient as u16,
+            element_string,
+            gs1_checksum(&element_string)
+        )
+    }
+}
+
+// GS1 EPC TDS Section 14.5.6
+pub(super) fn decode_gsrn96(data: &[u8]) -> Result<GSRN96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, service_reference_bits, service_reference_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let service_reference = read_digits(
+        &mut reader,
+        service_reference_bits,
+        service_reference_digits,
+        "service_reference",
+    )?;
+
+    Ok(GSRN96 {
+        filter,
+        partition,
+        company_prefix,
+        service_reference,
+    })
+}
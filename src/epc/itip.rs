@@ -0,0 +1,96 @@
+//! Individual Trade Item Piece
+//!
+//! This identifies one piece of a trade item which is delivered in several pieces, such as a
+//! piece of furniture shipped in more than one box. It's a combination of the GTIN of the whole
+//! trade item, the piece number, the total number of pieces, and a serial number.
+use crate::epc::sgtin::{read_gtin, write_gtin_fields};
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::{ApplicationIdentifier, GS1, GTIN};
+use alloc::{boxed::Box, format, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 110-bit Individual Trade Item Piece
+///
+/// This comprises a GTIN, a filter value (which is used by RFID readers), a piece number, the
+/// total number of pieces, and a numeric serial number.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ITIP110 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Global Trade Item Number of the complete trade item
+    pub gtin: GTIN,
+    /// Number of this piece
+    pub piece: u8,
+    /// Total number of pieces
+    pub total: u8,
+    /// Serial number
+    pub serial: u64,
+}
+
+impl ITIP110 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write_gtin_fields(w, &self.gtin)?;
+        write!(w, ".{:02}.{:02}.{}", self.piece, self.total, self.serial)
+    }
+}
+
+impl EPC for ITIP110 {
+    // GS1 EPC TDS section 6.3.13
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:itip:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:itip-110:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        110
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::ITIP110(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::ITIP110(*self)
+    }
+}
+
+impl GS1 for ITIP110 {
+    fn to_gs1(&self) -> String {
+        format!(
+            "({}) {}{:02}{:02} ({:0>2}) {}",
+            ApplicationIdentifier::ITIP as u16,
+            self.gtin.to_gtin14(),
+            self.piece,
+            self.total,
+            ApplicationIdentifier::SerialNumber as u16,
+            self.serial
+        )
+    }
+}
+
+// GS1 EPC TDS Section 14.6.14
+pub(super) fn decode_itip110(data: &[u8]) -> Result<ITIP110> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let gtin = read_gtin(&mut reader)?;
+    let piece = reader.read_u8(7)?;
+    let total = reader.read_u8(7)?;
+    let serial = reader.read_u64(38)?;
+
+    Ok(ITIP110 {
+        filter,
+        gtin,
+        piece,
+        total,
+        serial,
+    })
+}
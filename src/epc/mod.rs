@@ -3,37 +3,52 @@
 //! EPCs are used to represent GS1 IDs on Gen2 RFID tags.
 //! This is documented in the [GS1 EPC Tag Data Standard](https://www.gs1.org/standards/epc-rfid/tds).
 //!
-use crate::error::{Result, UnimplementedError};
+use crate::error::{Gs1Error, Result};
+use crate::util::parse_decimal;
+use crate::GS1;
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::fmt;
 use num_enum::TryFromPrimitive;
-use std::convert::TryFrom;
 
+pub mod adi;
+pub mod cpi;
+pub mod gdti;
+pub mod giai;
 pub mod gid;
 pub mod grai;
+pub mod gsrn;
+pub mod itip;
+mod partition;
+pub mod sgcn;
+pub mod sgln;
 pub mod sgtin;
 pub mod sscc;
 pub mod tid;
+pub mod usdod;
 
+/// The header byte of a binary EPC, which identifies its encoding scheme.
 // EPC Table 14-1
 #[derive(Debug, Eq, PartialEq, TryFromPrimitive, Copy, Clone)]
 #[repr(u8)]
 #[allow(clippy::upper_case_acronyms)]
-enum EPCBinaryHeader {
+pub enum EPCBinaryHeader {
     Unprogrammed = 0x00,
-    GTDI96 = 0x2C,
+    GDTI96 = 0x2C,
     GSRN96 = 0x2D,
     GSRNP = 0x2E,
     USDoD96 = 0x2F,
-    SGITN96 = 0x30,
+    SGTIN96 = 0x30,
     SSCC96 = 0x31,
     SGLN96 = 0x32,
     GRAI96 = 0x33,
     GIAI96 = 0x34,
     GID96 = 0x35,
-    SGITN198 = 0x36,
+    SGTIN198 = 0x36,
     GRAI170 = 0x37,
     GIAI202 = 0x38,
     SGLN195 = 0x39,
-    GTDI113 = 0x3A,
+    GDTI113 = 0x3A,
     ADIVAR = 0x3B,
     CPI96 = 0x3C,
     CPIVAR = 0x3D,
@@ -43,74 +58,1200 @@ enum EPCBinaryHeader {
     ITIP212 = 0x41,
 }
 
+impl EPCBinaryHeader {
+    /// Return the number of bits in this encoding, including the header.
+    ///
+    /// Returns `None` for variable-length encodings, and for unprogrammed tags.
+    // GS1 EPC TDS Table 14-1
+    pub fn bit_length(&self) -> Option<usize> {
+        use EPCBinaryHeader::*;
+        match self {
+            Unprogrammed | ADIVAR | CPIVAR => None,
+            GDTI96 | GSRN96 | GSRNP | USDoD96 | SGTIN96 | SSCC96 | SGLN96 | GRAI96 | GIAI96
+            | GID96 | CPI96 | SGCN96 => Some(96),
+            GDTI113 => Some(113),
+            ITIP110 => Some(110),
+            GRAI170 => Some(170),
+            GDTI174 => Some(174),
+            SGLN195 => Some(195),
+            SGTIN198 => Some(198),
+            GIAI202 => Some(202),
+            ITIP212 => Some(212),
+        }
+    }
+
+    /// Return the name of this encoding scheme, as used in the EPC tag URI.
+    ///
+    /// Example: `sgtin-96`
+    // GS1 EPC TDS Table 14-1
+    pub fn scheme_name(&self) -> &'static str {
+        use EPCBinaryHeader::*;
+        match self {
+            Unprogrammed => "unprogrammed",
+            GDTI96 => "gdti-96",
+            GSRN96 => "gsrn-96",
+            GSRNP => "gsrnp-96",
+            USDoD96 => "usdod-96",
+            SGTIN96 => "sgtin-96",
+            SSCC96 => "sscc-96",
+            SGLN96 => "sgln-96",
+            GRAI96 => "grai-96",
+            GIAI96 => "giai-96",
+            GID96 => "gid-96",
+            SGTIN198 => "sgtin-198",
+            GRAI170 => "grai-170",
+            GIAI202 => "giai-202",
+            SGLN195 => "sgln-195",
+            GDTI113 => "gdti-113",
+            ADIVAR => "adi-var",
+            CPI96 => "cpi-96",
+            CPIVAR => "cpi-var",
+            GDTI174 => "gdti-174",
+            SGCN96 => "sgcn-96",
+            ITIP110 => "itip-110",
+            ITIP212 => "itip-212",
+        }
+    }
+
+    // Check that the data (including the header byte) is long enough for this encoding.
+    fn check_length(&self, data: &[u8]) -> Result<()> {
+        match self.bit_length() {
+            Some(bits) if data.len() * 8 < bits => Err(Gs1Error::TooShort {
+                needed: bits.div_ceil(8),
+                got: data.len(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return the standard meaning of a filter value in the given scheme, for display.
+///
+/// Returns `None` if the scheme has no filter field (such as GID-96), if its filter values
+/// aren't covered here, or if the value doesn't fit in the 3-bit filter field. Values reserved
+/// for future use are described as `"Reserved"`.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{filter_meaning, EPCBinaryHeader};
+/// assert_eq!(
+///     filter_meaning(EPCBinaryHeader::SSCC96, 2),
+///     Some("Full case for transport")
+/// );
+/// ```
+// GS1 EPC TDS Section 10
+pub fn filter_meaning(scheme: EPCBinaryHeader, filter: u8) -> Option<&'static str> {
+    use EPCBinaryHeader::*;
+    if filter > 7 {
+        return None;
+    }
+    Some(match (scheme, filter) {
+        (Unprogrammed | GID96 | USDoD96 | ADIVAR, _) => return None,
+        (_, 0) => "All others",
+        (SGTIN96 | SGTIN198 | ITIP110 | ITIP212, 1) => "Point of sale trade item",
+        (SGTIN96 | SGTIN198 | ITIP110 | ITIP212 | SSCC96, 2) => "Full case for transport",
+        (SGTIN96 | SGTIN198 | ITIP110 | ITIP212, 4) => {
+            "Inner pack trade item grouping for handling"
+        }
+        (SGTIN96 | SGTIN198 | ITIP110 | ITIP212 | SSCC96, 6) => "Unit load",
+        (SGTIN96 | SGTIN198 | ITIP110 | ITIP212, 7) => {
+            "Unit inside trade item or component inside a product not intended for individual sale"
+        }
+        (SGLN96 | SGLN195, 1) => "Physical location",
+        (GIAI96 | GIAI202, 1) => "Rail vehicle",
+        (GDTI96 | GDTI113 | GDTI174, 1) => "Travel document",
+        _ => "Reserved",
+    })
+}
+
 /// A GS1 object which is capable of being represented as an EPC.
 pub trait EPC {
     /// Return the EPC pure identity URI for this object.
     ///
     /// Example: `urn:epc:id:sgtin:0614141.812345.6789`
-    fn to_uri(&self) -> String;
+    fn to_uri(&self) -> String {
+        let mut uri = String::new();
+        self.write_uri(&mut uri)
+            .expect("writing to a String can't fail");
+        uri
+    }
+    /// Return the pure identity of this EPC, for comparing EPCs from different tags.
+    ///
+    /// This is the pure identity URI, the same as `to_uri`. It identifies the object regardless
+    /// of how it was encoded on the tag, so it intentionally ignores the filter value and the
+    /// size of the encoding. For example, an SGTIN-96 and an SGTIN-198 with the same GTIN and
+    /// serial number have the same pure identity.
+    fn pure_identity(&self) -> String {
+        self.to_uri()
+    }
     /// Return the EPC tag URI for this object.
     ///
     /// This URI includes all data from the pure URI, plus tag-specific data which does not form
     /// part of the identifier.
     ///
     /// Example: `urn:epc:tag:sgtin-96:3.0614141.812345.6789`
-    fn to_tag_uri(&self) -> String;
+    fn to_tag_uri(&self) -> String {
+        let mut uri = String::new();
+        self.write_tag_uri(&mut uri)
+            .expect("writing to a String can't fail");
+        uri
+    }
+    /// Write the EPC pure identity URI for this object to `w`.
+    ///
+    /// Unlike `to_uri`, this doesn't allocate a `String` for each URI, so many URIs can be
+    /// written into a single buffer.
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result;
+    /// Write the EPC tag URI for this object to `w`.
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result;
+    /// Return the number of bits in the binary encoding of this EPC, including the header.
+    ///
+    /// This is the canonical size of the encoding, such as 198 for an SGTIN-198. The EPC memory
+    /// bank is written in 16-bit words, so use `word_length` for the size to write.
+    fn bit_length(&self) -> usize;
+    /// Return the number of 16-bit words needed to write this EPC to the EPC memory bank of a tag.
+    ///
+    /// This is `bit_length` rounded up to a whole number of words, with the encoding padded with
+    /// zeros, and is the length given in the tag's PC word.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::decode_hex;
+    /// let epc = decode_hex("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    /// assert_eq!(epc.bit_length(), 198);
+    /// assert_eq!(epc.word_length(), 13);
+    /// ```
+    // EPC UHF Gen2 Air Interface Protocol Section 6.3.2.1.2.2
+    fn word_length(&self) -> usize {
+        self.bit_length().div_ceil(16)
+    }
+    /// Return the GS1 element string for this EPC, if it has one.
+    ///
+    /// Some EPC schemes, such as GID and the US DoD identifier, have no corresponding GS1
+    /// Application Identifier, so this returns `None` for them.
+    ///
+    /// Example: `Some("(01) 80614141123458 (21) 6789")`
+    fn to_gs1_opt(&self) -> Option<String> {
+        self.get_value().to_gs1_opt()
+    }
+    /// Encode this EPC to its binary form, as written to the EPC memory bank of a tag.
+    ///
+    /// This is the inverse of `decode_binary`. The result is padded with zeros to a whole number
+    /// of bytes. Returns `Gs1Error::Unimplemented` for schemes which can't be encoded yet.
+    fn to_binary(&self) -> Result<Vec<u8>> {
+        self.get_value().to_binary()
+    }
     /// Return the underlying EPC structure in an `EPCValue` tagged enum.
-    fn get_value(&self) -> EPCValue;
+    fn get_value(&self) -> EPCValue<'_>;
+    /// Return the underlying EPC structure in an `OwnedEPCValue` tagged enum, consuming this
+    /// object.
+    ///
+    /// Unlike `get_value`, the result doesn't borrow from the decoded EPC, so it can be stored.
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue;
+}
+
+/// Display an EPC as its pure identity URI.
+impl fmt::Display for dyn EPC + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_uri(f)
+    }
+}
+
+/// Check whether two EPCs identify the same object, by comparing their pure identities.
+///
+/// The filter value and encoding scheme size are ignored, so this can be used to deduplicate
+/// reads of the same object from differently-encoded tags.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{from_tag_uri, same_identity};
+/// let a = from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.6789").unwrap();
+/// let b = from_tag_uri("urn:epc:tag:sgtin-198:1.0614141.812345.6789").unwrap();
+/// assert!(same_identity(a.as_ref(), b.as_ref()));
+/// ```
+pub fn same_identity(a: &dyn EPC, b: &dyn EPC) -> bool {
+    a.pure_identity() == b.pure_identity()
 }
 
 /// Represents an unprogrammed tag (with the header byte 0x00)
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unprogrammed {
     pub data: Vec<u8>,
 }
 
 impl EPC for Unprogrammed {
-    fn to_uri(&self) -> String {
-        "urn:epc:id:unprogrammed".to_string()
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        w.write_str("urn:epc:id:unprogrammed")
     }
 
-    fn to_tag_uri(&self) -> String {
-        "urn:epc:tag:unprogrammed".to_string()
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        w.write_str("urn:epc:tag:unprogrammed")
+    }
+
+    fn bit_length(&self) -> usize {
+        8 * (1 + self.data.len())
     }
 
-    fn get_value(&self) -> EPCValue {
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::Unprogrammed(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::Unprogrammed(*self)
+    }
+}
+
+/// Represents a tag with a header which this library can't decode.
+///
+/// This is returned for valid headers of schemes which can't be decoded here, and for invalid
+/// headers when `DecodeOptions::unknown_as_raw` is set.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Raw {
+    /// Header byte
+    pub header: u8,
+    /// Tag data following the header byte
+    pub data: Vec<u8>,
+}
+
+impl Raw {
+    // EPC Raw URI, GS1 EPC TDS Section 12
+    fn write_raw_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
+            "urn:epc:raw:{}.x{:02X}",
+            (self.data.len() + 1) * 8,
+            self.header
+        )?;
+        for byte in self.data.iter() {
+            write!(w, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl EPC for Raw {
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_raw_uri(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_raw_uri(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        // Use the length of the encoding where it's known, as the data may be padded
+        EPCBinaryHeader::try_from(self.header)
+            .ok()
+            .and_then(|header| header.bit_length())
+            .unwrap_or(8 * (1 + self.data.len()))
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::Raw(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::Raw(*self)
+    }
 }
 
 /// A tagged union to allow data structures to be returned from the EPC trait
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "scheme"))]
 pub enum EPCValue<'a> {
+    #[cfg_attr(feature = "serde", serde(rename = "unprogrammed"))]
     Unprogrammed(&'a Unprogrammed),
+    #[cfg_attr(feature = "serde", serde(rename = "raw"))]
+    Raw(&'a Raw),
+    #[cfg_attr(feature = "serde", serde(rename = "sgtin-96"))]
     SGTIN96(&'a sgtin::SGTIN96),
+    #[cfg_attr(feature = "serde", serde(rename = "sgtin-198"))]
     SGTIN198(&'a sgtin::SGTIN198),
+    #[cfg_attr(feature = "serde", serde(rename = "sscc-96"))]
     SSCC96(&'a sscc::SSCC96),
+    #[cfg_attr(feature = "serde", serde(rename = "gid-96"))]
     GID96(&'a gid::GID96),
+    #[cfg_attr(feature = "serde", serde(rename = "grai-96"))]
     GRAI96(&'a grai::GRAI96),
+    #[cfg_attr(feature = "serde", serde(rename = "grai-170"))]
+    GRAI170(&'a grai::GRAI170),
+    #[cfg_attr(feature = "serde", serde(rename = "sgln-96"))]
+    SGLN96(&'a sgln::SGLN96),
+    #[cfg_attr(feature = "serde", serde(rename = "giai-96"))]
+    GIAI96(&'a giai::GIAI96),
+    #[cfg_attr(feature = "serde", serde(rename = "gdti-96"))]
+    GDTI96(&'a gdti::GDTI96),
+    #[cfg_attr(feature = "serde", serde(rename = "gdti-174"))]
+    GDTI174(&'a gdti::GDTI174),
+    #[cfg_attr(feature = "serde", serde(rename = "gsrn-96"))]
+    GSRN96(&'a gsrn::GSRN96),
+    #[cfg_attr(feature = "serde", serde(rename = "usdod-96"))]
+    USDoD96(&'a usdod::USDoD96),
+    #[cfg_attr(feature = "serde", serde(rename = "sgcn-96"))]
+    SGCN96(&'a sgcn::SGCN96),
+    #[cfg_attr(feature = "serde", serde(rename = "cpi-96"))]
+    CPI96(&'a cpi::CPI96),
+    #[cfg_attr(feature = "serde", serde(rename = "cpi-var"))]
+    CPIVar(&'a cpi::CPIVar),
+    #[cfg_attr(feature = "serde", serde(rename = "itip-110"))]
+    ITIP110(&'a itip::ITIP110),
+    #[cfg_attr(feature = "serde", serde(rename = "adi-var"))]
+    ADIVar(&'a adi::ADIVar),
+}
+
+impl EPCValue<'_> {
+    fn as_epc(&self) -> &dyn EPC {
+        match *self {
+            EPCValue::Unprogrammed(val) => val,
+            EPCValue::Raw(val) => val,
+            EPCValue::SGTIN96(val) => val,
+            EPCValue::SGTIN198(val) => val,
+            EPCValue::SSCC96(val) => val,
+            EPCValue::GID96(val) => val,
+            EPCValue::GRAI96(val) => val,
+            EPCValue::GRAI170(val) => val,
+            EPCValue::SGLN96(val) => val,
+            EPCValue::GIAI96(val) => val,
+            EPCValue::GDTI96(val) => val,
+            EPCValue::GDTI174(val) => val,
+            EPCValue::GSRN96(val) => val,
+            EPCValue::USDoD96(val) => val,
+            EPCValue::SGCN96(val) => val,
+            EPCValue::CPI96(val) => val,
+            EPCValue::CPIVar(val) => val,
+            EPCValue::ITIP110(val) => val,
+            EPCValue::ADIVar(val) => val,
+        }
+    }
+
+    fn to_gs1_opt(self) -> Option<String> {
+        match self {
+            EPCValue::SGTIN96(val) => Some(val.to_gs1()),
+            EPCValue::SGTIN198(val) => Some(val.to_gs1()),
+            EPCValue::SSCC96(val) => Some(val.to_gs1()),
+            // The GRAI element string is meaningless without a valid partition
+            EPCValue::GRAI96(val) => val.to_grai().ok().map(|_| val.to_gs1()),
+            EPCValue::GRAI170(val) => val.to_grai().ok().map(|_| val.to_gs1()),
+            EPCValue::SGLN96(val) => Some(val.to_gs1()),
+            EPCValue::GIAI96(val) => Some(val.to_gs1()),
+            EPCValue::GDTI96(val) => Some(val.to_gs1()),
+            EPCValue::GDTI174(val) => Some(val.to_gs1()),
+            EPCValue::GSRN96(val) => Some(val.to_gs1()),
+            EPCValue::SGCN96(val) => Some(val.to_gs1()),
+            EPCValue::ITIP110(val) => Some(val.to_gs1()),
+            // These schemes have no GS1 element string
+            EPCValue::Unprogrammed(_)
+            | EPCValue::Raw(_)
+            | EPCValue::GID96(_)
+            | EPCValue::USDoD96(_)
+            | EPCValue::CPI96(_)
+            | EPCValue::CPIVar(_)
+            | EPCValue::ADIVar(_) => None,
+        }
+    }
+
+    fn to_binary(self) -> Result<Vec<u8>> {
+        let header = match self {
+            EPCValue::Unprogrammed(val) => {
+                let mut data = Vec::with_capacity(1 + val.data.len());
+                data.push(EPCBinaryHeader::Unprogrammed as u8);
+                data.extend_from_slice(&val.data);
+                return Ok(data);
+            }
+            EPCValue::Raw(val) => {
+                let mut data = Vec::with_capacity(1 + val.data.len());
+                data.push(val.header);
+                data.extend_from_slice(&val.data);
+                return Ok(data);
+            }
+            EPCValue::SGTIN96(val) => return sgtin::encode_sgtin96(val),
+            EPCValue::SGTIN198(val) => return sgtin::encode_sgtin198(val),
+            EPCValue::GID96(val) => return gid::encode_gid96(val),
+            EPCValue::SSCC96(_) => EPCBinaryHeader::SSCC96,
+            EPCValue::GRAI96(val) => return grai::encode_grai96(val),
+            EPCValue::GRAI170(_) => EPCBinaryHeader::GRAI170,
+            EPCValue::SGLN96(_) => EPCBinaryHeader::SGLN96,
+            EPCValue::GIAI96(_) => EPCBinaryHeader::GIAI96,
+            EPCValue::GDTI96(_) => EPCBinaryHeader::GDTI96,
+            EPCValue::GDTI174(_) => EPCBinaryHeader::GDTI174,
+            EPCValue::GSRN96(_) => EPCBinaryHeader::GSRN96,
+            EPCValue::USDoD96(_) => EPCBinaryHeader::USDoD96,
+            EPCValue::SGCN96(_) => EPCBinaryHeader::SGCN96,
+            EPCValue::CPI96(_) => EPCBinaryHeader::CPI96,
+            EPCValue::CPIVar(_) => EPCBinaryHeader::CPIVAR,
+            EPCValue::ITIP110(_) => EPCBinaryHeader::ITIP110,
+            EPCValue::ADIVar(_) => EPCBinaryHeader::ADIVAR,
+        };
+        Err(Gs1Error::Unimplemented(header))
+    }
+}
+
+/// Display the EPC as its pure identity URI.
+impl fmt::Display for EPCValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_epc().write_uri(f)
+    }
+}
+
+/// A tagged union which owns the decoded EPC structure
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "scheme"))]
+pub enum OwnedEPCValue {
+    #[cfg_attr(feature = "serde", serde(rename = "unprogrammed"))]
+    Unprogrammed(Unprogrammed),
+    #[cfg_attr(feature = "serde", serde(rename = "raw"))]
+    Raw(Raw),
+    #[cfg_attr(feature = "serde", serde(rename = "sgtin-96"))]
+    SGTIN96(sgtin::SGTIN96),
+    #[cfg_attr(feature = "serde", serde(rename = "sgtin-198"))]
+    SGTIN198(sgtin::SGTIN198),
+    #[cfg_attr(feature = "serde", serde(rename = "sscc-96"))]
+    SSCC96(sscc::SSCC96),
+    #[cfg_attr(feature = "serde", serde(rename = "gid-96"))]
+    GID96(gid::GID96),
+    #[cfg_attr(feature = "serde", serde(rename = "grai-96"))]
+    GRAI96(grai::GRAI96),
+    #[cfg_attr(feature = "serde", serde(rename = "grai-170"))]
+    GRAI170(grai::GRAI170),
+    #[cfg_attr(feature = "serde", serde(rename = "sgln-96"))]
+    SGLN96(sgln::SGLN96),
+    #[cfg_attr(feature = "serde", serde(rename = "giai-96"))]
+    GIAI96(giai::GIAI96),
+    #[cfg_attr(feature = "serde", serde(rename = "gdti-96"))]
+    GDTI96(gdti::GDTI96),
+    #[cfg_attr(feature = "serde", serde(rename = "gdti-174"))]
+    GDTI174(gdti::GDTI174),
+    #[cfg_attr(feature = "serde", serde(rename = "gsrn-96"))]
+    GSRN96(gsrn::GSRN96),
+    #[cfg_attr(feature = "serde", serde(rename = "usdod-96"))]
+    USDoD96(usdod::USDoD96),
+    #[cfg_attr(feature = "serde", serde(rename = "sgcn-96"))]
+    SGCN96(sgcn::SGCN96),
+    #[cfg_attr(feature = "serde", serde(rename = "cpi-96"))]
+    CPI96(cpi::CPI96),
+    #[cfg_attr(feature = "serde", serde(rename = "cpi-var"))]
+    CPIVar(cpi::CPIVar),
+    #[cfg_attr(feature = "serde", serde(rename = "itip-110"))]
+    ITIP110(itip::ITIP110),
+    #[cfg_attr(feature = "serde", serde(rename = "adi-var"))]
+    ADIVar(adi::ADIVar),
+}
+
+impl OwnedEPCValue {
+    fn as_epc(&self) -> &dyn EPC {
+        match self {
+            OwnedEPCValue::Unprogrammed(val) => val,
+            OwnedEPCValue::Raw(val) => val,
+            OwnedEPCValue::SGTIN96(val) => val,
+            OwnedEPCValue::SGTIN198(val) => val,
+            OwnedEPCValue::SSCC96(val) => val,
+            OwnedEPCValue::GID96(val) => val,
+            OwnedEPCValue::GRAI96(val) => val,
+            OwnedEPCValue::GRAI170(val) => val,
+            OwnedEPCValue::SGLN96(val) => val,
+            OwnedEPCValue::GIAI96(val) => val,
+            OwnedEPCValue::GDTI96(val) => val,
+            OwnedEPCValue::GDTI174(val) => val,
+            OwnedEPCValue::GSRN96(val) => val,
+            OwnedEPCValue::USDoD96(val) => val,
+            OwnedEPCValue::SGCN96(val) => val,
+            OwnedEPCValue::CPI96(val) => val,
+            OwnedEPCValue::CPIVar(val) => val,
+            OwnedEPCValue::ITIP110(val) => val,
+            OwnedEPCValue::ADIVar(val) => val,
+        }
+    }
+}
+
+/// Display the EPC as its pure identity URI.
+impl fmt::Display for OwnedEPCValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_epc().write_uri(f)
+    }
+}
+
+impl EPC for OwnedEPCValue {
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_epc().write_uri(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_epc().write_tag_uri(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        self.as_epc().bit_length()
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        self.as_epc().get_value()
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        *self
+    }
 }
 
 fn take_header(data: &[u8]) -> Result<(&[u8], EPCBinaryHeader)> {
-    let header = EPCBinaryHeader::try_from(data[0])?;
-    Ok((&data[1..], header))
+    let (header, body) = data.split_first().ok_or(Gs1Error::Parse)?;
+    let header = EPCBinaryHeader::try_from(*header)?;
+    Ok((body, header))
+}
+
+/// Return the header of a binary EPC code without decoding the rest of it.
+///
+/// This allows tags to be cheaply filtered by scheme before a full decode. The header may
+/// belong to a scheme which `decode_binary` doesn't implement.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{peek_header, EPCBinaryHeader};
+/// let header = peek_header(&[0x30, 0x74, 0x25, 0x7B]).unwrap();
+/// assert_eq!(header, EPCBinaryHeader::SGTIN96);
+/// assert_eq!(header.scheme_name(), "sgtin-96");
+/// assert!(peek_header(&[]).is_err());
+/// ```
+pub fn peek_header(data: &[u8]) -> Result<EPCBinaryHeader> {
+    Ok(take_header(data)?.1)
+}
+
+/// Return the EPC schemes which `decode_binary` can decode.
+///
+/// Tags with other valid headers are returned as `Raw`. Unprogrammed tags aren't an EPC scheme so
+/// aren't included, although they're always decoded.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{supported_schemes, EPCBinaryHeader};
+/// assert!(supported_schemes().contains(&EPCBinaryHeader::SGTIN96));
+/// assert!(!supported_schemes().contains(&EPCBinaryHeader::GIAI202));
+/// ```
+pub fn supported_schemes() -> &'static [EPCBinaryHeader] {
+    use EPCBinaryHeader::*;
+    &[
+        ADIVAR, CPI96, CPIVAR, GDTI96, GDTI174, GIAI96, GID96, GRAI96, GRAI170, GSRN96, ITIP110,
+        SGTIN96, SGTIN198, SGCN96, SGLN96, SSCC96, USDoD96,
+    ]
 }
 
-/// Decode a binary EPC code, as received from an RFID tag.
+// Whether `decode_binary` can decode tags with this header.
+fn is_implemented(header: EPCBinaryHeader) -> bool {
+    header == EPCBinaryHeader::Unprogrammed || supported_schemes().contains(&header)
+}
+
+/// Return the partition value for a GS1 company prefix with `company_digits` digits in an EPC
+/// scheme.
+///
+/// Encoding an EPC requires the partition value, which determines how the bits are divided
+/// between the company prefix and the field which follows it. Returns
+/// `Gs1Error::OutOfRange` if `company_digits` isn't between 6 and 12, and
+/// `Gs1Error::Unimplemented` if the scheme has no partition table here.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{partition_for_company_digits, EPCBinaryHeader};
+/// assert_eq!(partition_for_company_digits(EPCBinaryHeader::SGTIN96, 7).unwrap(), 5);
+/// assert!(partition_for_company_digits(EPCBinaryHeader::SGTIN96, 5).is_err());
+/// ```
+pub fn partition_for_company_digits(scheme: EPCBinaryHeader, company_digits: usize) -> Result<u8> {
+    partition::for_scheme(scheme)
+        .ok_or(Gs1Error::Unimplemented(scheme))?
+        .partition(company_digits)
+}
+
+/// Options to control the behaviour of `decode_binary_with_options`.
+///
+/// Options may be added in future versions, so start from the defaults and set the ones you need.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{decode_binary_with_options, DecodeOptions};
+/// let options = DecodeOptions::new().with_unknown_as_raw(true);
+/// let epc = decode_binary_with_options(&[0xE2; 12], &options).unwrap();
+/// assert_eq!(epc.to_uri(), "urn:epc:raw:96.x".to_string() + &"E2".repeat(12));
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+#[non_exhaustive]
+pub struct DecodeOptions {
+    /// Return tags with a header byte which isn't defined by the standard as `Raw` rather than
+    /// erroring. Tags with a valid header which this library can't decode are always returned as
+    /// `Raw`.
+    pub unknown_as_raw: bool,
+    /// Return `Gs1Error::Parse` if any reserved bits, the padding after the terminator of a
+    /// serial, or the padding after the encoding up to the next 16-bit word, are non-zero.
+    pub strict: bool,
+}
+
+impl DecodeOptions {
+    /// Return the default options, which are the same as `decode_binary` uses.
+    pub fn new() -> DecodeOptions {
+        DecodeOptions::default()
+    }
+
+    /// Set whether tags with an undefined header byte are returned as `Raw`.
+    pub fn with_unknown_as_raw(mut self, unknown_as_raw: bool) -> DecodeOptions {
+        self.unknown_as_raw = unknown_as_raw;
+        self
+    }
+
+    /// Set whether non-zero reserved bits and padding are rejected.
+    pub fn with_strict(mut self, strict: bool) -> DecodeOptions {
+        self.strict = strict;
+        self
+    }
+}
+
+/// Decode a binary EPC code, as received from an RFID tag.
+///
+/// Returns `Gs1Error::TooShort` if there are fewer bytes than the encoding indicated by the header
+/// requires. Tags with a valid header for a scheme which can't be decoded here are returned as
+/// `Raw`, so they can be kept and written back unchanged.
 pub fn decode_binary(data: &[u8]) -> Result<Box<dyn EPC>> {
-    let (data, header) = take_header(data)?;
+    decode_binary_with_options(data, &DecodeOptions::default())
+}
+
+/// Decode a binary EPC code, rejecting encodings which don't conform to the standard.
+///
+/// This is stricter than `decode_binary`, which ignores the values of reserved bits and padding
+/// as they're often set on tags in the field. Here they must be zero, otherwise
+/// `Gs1Error::Parse` is returned.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{decode_binary, decode_binary_strict};
+/// // An SSCC-96 with its 24 reserved bits set to 1
+/// let data = hex::decode("3174257BF4499602D2000001").unwrap();
+/// assert!(decode_binary(&data).is_ok());
+/// assert!(decode_binary_strict(&data).is_err());
+/// ```
+pub fn decode_binary_strict(data: &[u8]) -> Result<Box<dyn EPC>> {
+    decode_binary_with_options(data, &DecodeOptions::new().with_strict(true))
+}
+
+/// Decode a binary EPC code which is preceded by its Gen2 Protocol Control (PC) word.
+///
+/// Many readers return the PC word along with the EPC, as the tag sends them together when it's
+/// inventoried. `decode_binary` expects the EPC to start at its header byte, so this should be
+/// used for input in this layout:
+///
+/// | Bytes  | Contents                                                                       |
+/// |--------|--------------------------------------------------------------------------------|
+/// | 0-1    | PC word: EPC length in 16-bit words (5 bits), UMI, XI and T bits, and 8 bits of EPC attributes or ISO AFI |
+/// | 2-3    | XPC_W1, only present if the XI bit of the PC word is set                       |
+/// | 4-5    | XPC_W2, only present if the XEB bit (the most significant bit) of XPC_W1 is set |
+/// | ...    | The EPC, of the length given by the PC word                                    |
+///
+/// Any data after the EPC, such as a CRC, is ignored. Returns `Gs1Error::Parse` if the T bit is
+/// set, as the tag then contains an ISO 15961 unique item identifier rather than a GS1 EPC.
+///
+/// For fixed-length encodings, the length in the PC word must match the length of the encoding
+/// given by the EPC header, rounded up to whole words. Otherwise `Gs1Error::LengthMismatch` is
+/// returned, which usually indicates a bad read.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::decode_binary_with_pc;
+/// let data = hex::decode("30003074257BF7194E4000001A85").unwrap();
+/// let epc = decode_binary_with_pc(&data).unwrap();
+/// assert_eq!(epc.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
+/// ```
+// EPC UHF Gen2 Air Interface Protocol Sections 6.3.2.1.2.2 and 6.3.2.1.2.5
+pub fn decode_binary_with_pc(data: &[u8]) -> Result<Box<dyn EPC>> {
+    let epc = strip_pc(data)?;
+    if let Some(bits) = peek_header(epc).ok().and_then(|header| header.bit_length()) {
+        if epc.len() / 2 != bits.div_ceil(16) {
+            return Err(Gs1Error::LengthMismatch {
+                pc: epc.len() / 2,
+                header: bits.div_ceil(16),
+            });
+        }
+    }
+    decode_binary(epc)
+}
+
+// Return the EPC which follows the PC word (and any XPC words) in `data`.
+fn strip_pc(data: &[u8]) -> Result<&[u8]> {
+    let word = |i: usize| -> Result<u16> {
+        match data.get(i * 2..i * 2 + 2) {
+            Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+            None => Err(Gs1Error::TooShort {
+                needed: i * 2 + 2,
+                got: data.len(),
+            }),
+        }
+    };
+
+    let pc = word(0)?;
+    let epc_words = (pc >> 11) as usize;
+    let xi = pc & 0x0200 != 0;
+    let toggle = pc & 0x0100 != 0;
+    if toggle {
+        return Err(Gs1Error::Parse);
+    }
+
+    let mut start = 1;
+    if xi {
+        let xpc_w1 = word(1)?;
+        start += if xpc_w1 & 0x8000 != 0 { 2 } else { 1 };
+    }
+
+    let end = (start + epc_words) * 2;
+    data.get(start * 2..end).ok_or(Gs1Error::TooShort {
+        needed: end,
+        got: data.len(),
+    })
+}
+
+/// Decode a binary EPC code and return its pure identity URI.
+///
+/// This is a shortcut for `decode_binary(data)?.to_uri()`, and returns the same errors as
+/// `decode_binary`.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::binary_to_uri;
+/// let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+/// assert_eq!(binary_to_uri(&data).unwrap(), "urn:epc:id:sgtin:0614141.812345.6789");
+/// ```
+pub fn binary_to_uri(data: &[u8]) -> Result<String> {
+    Ok(decode_binary(data)?.to_uri())
+}
+
+/// Decode a binary EPC code written as a hex string.
+///
+/// Whitespace and an optional `0x` prefix are ignored. Returns `Gs1Error::Parse` if the string
+/// isn't valid hex.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::decode_hex;
+/// let epc = decode_hex("0x3074 257B F719 4E40 0000 1A85").unwrap();
+/// assert_eq!(epc.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
+/// ```
+pub fn decode_hex(s: &str) -> Result<Box<dyn EPC>> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    let digits: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    let data = hex::decode(digits).map_err(|_| Gs1Error::Parse)?;
+    decode_binary(&data)
+}
+
+/// Decode a binary EPC code held in the lowest `bits` bits of an integer.
+///
+/// This is convenient where an EPC of up to 128 bits is stored as an integer, such as in a
+/// database column. Returns `Gs1Error::OutOfRange` if `bits` isn't between 1 and 128, or if
+/// `value` doesn't fit in that many bits.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::decode_u128;
+/// let epc = decode_u128(0x3074257BF7194E4000001A85, 96).unwrap();
+/// assert_eq!(epc.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
+/// ```
+pub fn decode_u128(value: u128, bits: u32) -> Result<Box<dyn EPC>> {
+    if !(1..=128).contains(&bits) {
+        return Err(Gs1Error::OutOfRange("bits"));
+    }
+    if bits < 128 && value >> bits != 0 {
+        return Err(Gs1Error::OutOfRange("value"));
+    }
+    // Move the EPC to the most significant bits, so any padding is at the end
+    let bytes = (value << (128 - bits)).to_be_bytes();
+    decode_binary(&bytes[..bits.div_ceil(8) as usize])
+}
+
+/// Decode a buffer containing several 96-bit binary EPC codes, one after another.
+///
+/// RFID readers often return the EPCs of several tags concatenated together. The header of
+/// each EPC determines its length, so only 96-bit encodings are supported. If a header is
+/// invalid or belongs to another encoding, the position of the next EPC is unknown, so an error
+/// is returned for it and decoding stops. Errors decoding a 96-bit EPC don't stop decoding.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::decode_binary_stream;
+/// let data = hex::decode("3074257BF7194E4000001A8535000007B0001C8000000315").unwrap();
+/// let epcs = decode_binary_stream(&data);
+/// assert_eq!(epcs.len(), 2);
+/// assert_eq!(
+///     epcs[1].as_ref().unwrap().to_uri(),
+///     "urn:epc:id:gid:123.456.789"
+/// );
+/// ```
+pub fn decode_binary_stream(data: &[u8]) -> Vec<Result<Box<dyn EPC>>> {
+    let mut epcs = Vec::new();
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let header = match peek_header(rest) {
+            Ok(header) => header,
+            Err(e) => {
+                epcs.push(Err(e));
+                break;
+            }
+        };
+        if header.bit_length() != Some(96) {
+            epcs.push(Err(Gs1Error::Unimplemented(header)));
+            break;
+        }
+
+        let (epc, next) = rest.split_at(rest.len().min(12));
+        epcs.push(decode_binary(epc));
+        rest = next;
+    }
+
+    epcs
+}
+
+/// Decode a binary EPC code with the provided options.
+pub fn decode_binary_with_options(data: &[u8], options: &DecodeOptions) -> Result<Box<dyn EPC>> {
+    Ok(Box::new(decode_typed(data, options)?))
+}
+
+/// Decode a binary EPC code directly into an `OwnedEPCValue`.
+///
+/// This avoids boxing the result where the caller is going to match on the type of EPC.
+pub fn decode_binary_typed(data: &[u8]) -> Result<OwnedEPCValue> {
+    decode_typed(data, &DecodeOptions::default())
+}
+
+// Decode a binary EPC directly into one concrete type. This returns `Gs1Error::Parse` if the
+// header belongs to a different scheme, without decoding the rest of the data.
+macro_rules! impl_try_from_binary {
+    ($($(#[$doc:meta])* $header:ident => $variant:ident($type:ty)),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            impl TryFrom<&[u8]> for $type {
+                type Error = Gs1Error;
+
+                fn try_from(data: &[u8]) -> Result<$type> {
+                    if peek_header(data)? != EPCBinaryHeader::$header {
+                        return Err(Gs1Error::Parse);
+                    }
+                    match decode_typed(data, &DecodeOptions::default())? {
+                        OwnedEPCValue::$variant(value) => Ok(value),
+                        _ => Err(Gs1Error::Parse),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_binary!(
+    /// Decode an SGTIN-96, which is 12 bytes long including the header.
+    SGTIN96 => SGTIN96(sgtin::SGTIN96),
+    /// Decode an SGTIN-198, which is 25 bytes long including the header.
+    SGTIN198 => SGTIN198(sgtin::SGTIN198),
+    /// Decode an SSCC-96, which is 12 bytes long including the header.
+    SSCC96 => SSCC96(sscc::SSCC96),
+    /// Decode a GID-96, which is 12 bytes long including the header.
+    GID96 => GID96(gid::GID96),
+    /// Decode a GRAI-96, which is 12 bytes long including the header.
+    GRAI96 => GRAI96(grai::GRAI96),
+    /// Decode a GRAI-170, which is 22 bytes long including the header.
+    GRAI170 => GRAI170(grai::GRAI170),
+    /// Decode an SGLN-96, which is 12 bytes long including the header.
+    SGLN96 => SGLN96(sgln::SGLN96),
+    /// Decode a GIAI-96, which is 12 bytes long including the header.
+    GIAI96 => GIAI96(giai::GIAI96),
+    /// Decode a GDTI-96, which is 12 bytes long including the header.
+    GDTI96 => GDTI96(gdti::GDTI96),
+    /// Decode a GSRN-96, which is 12 bytes long including the header.
+    GSRN96 => GSRN96(gsrn::GSRN96),
+    /// Decode a US DoD-96, which is 12 bytes long including the header.
+    USDoD96 => USDoD96(usdod::USDoD96),
+    /// Decode an SGCN-96, which is 12 bytes long including the header.
+    SGCN96 => SGCN96(sgcn::SGCN96),
+    /// Decode a CPI-96, which is 12 bytes long including the header.
+    CPI96 => CPI96(cpi::CPI96),
+    /// Decode an ITIP-110, which is 14 bytes long including the header.
+    ITIP110 => ITIP110(itip::ITIP110),
+    /// Decode a GDTI-174, which is 22 bytes long including the header.
+    GDTI174 => GDTI174(gdti::GDTI174),
+    /// Decode an ADI-var, whose length depends on its contents.
+    ADIVAR => ADIVar(adi::ADIVar),
+    /// Decode a CPI-var, whose length depends on its contents.
+    CPIVAR => CPIVar(cpi::CPIVar),
+);
+
+fn decode_typed(data: &[u8], options: &DecodeOptions) -> Result<OwnedEPCValue> {
+    // There's no header to return as Raw
+    if data.is_empty() {
+        return Err(Gs1Error::Parse);
+    }
+
+    let (body, header) = match take_header(data) {
+        Ok(result) => result,
+        Err(_e) if options.unknown_as_raw => return Ok(raw(data)),
+        Err(e) => return Err(e),
+    };
+    // Unimplemented schemes are kept whole, so the length of the data doesn't matter
+    if !is_implemented(header) {
+        return Ok(raw(data));
+    }
+    header.check_length(data)?;
+
+    // The scheme decoders read the data after the header byte, so lengths in their errors need
+    // adjusting to include it.
+    let value = decode_body(header, body).map_err(|err| match err {
+        Gs1Error::TooShort { needed, got } => Gs1Error::TooShort {
+            needed: needed + 1,
+            got: got + 1,
+        },
+        err => err,
+    })?;
 
+    if options.strict {
+        if let OwnedEPCValue::Unprogrammed(_) | OwnedEPCValue::Raw(_) = value {
+            return Ok(value);
+        }
+        let length = value.as_epc().bit_length();
+        // The SSCC-96 and GSRN-96 encodings end with 24 reserved bits.
+        // GS1 EPC TDS Sections 14.5.2 and 14.5.6
+        let start = match header {
+            EPCBinaryHeader::SSCC96 | EPCBinaryHeader::GSRN96 => 72,
+            _ => length,
+        };
+        let end = core::cmp::min(data.len() * 8, value.as_epc().word_length() * 16);
+        // The serial ends at its terminator, and the rest of its field is padding.
+        // GS1 EPC TDS Section 14.4.2
+        let (serial_start, serial_bits, serial_chars) = match &value {
+            OwnedEPCValue::SGTIN198(val) => (58, 140, val.serial.len()),
+            OwnedEPCValue::GRAI170(val) => (58, 112, val.serial.len()),
+            OwnedEPCValue::GDTI174(val) => (55, 119, val.serial.len()),
+            _ => (0, 0, 0),
+        };
+        let serial_padding = serial_start + core::cmp::min((serial_chars + 1) * 7, serial_bits)
+            ..serial_start + serial_bits;
+        if (start..end)
+            .chain(serial_padding)
+            .any(|i| data[i / 8] & (0x80 >> (i % 8)) != 0)
+        {
+            return Err(Gs1Error::Parse);
+        }
+    }
+    Ok(value)
+}
+
+fn decode_body(header: EPCBinaryHeader, body: &[u8]) -> Result<OwnedEPCValue> {
     Ok(match header {
-        EPCBinaryHeader::GID96 => gid::decode_gid96(data)?,
-        EPCBinaryHeader::GRAI96 => grai::decode_grai96(data)?,
-        EPCBinaryHeader::SGITN96 => sgtin::decode_sgtin96(data)?,
-        EPCBinaryHeader::SGITN198 => sgtin::decode_sgtin198(data)?,
-        EPCBinaryHeader::SSCC96 => sscc::decode_sscc96(data)?,
-        EPCBinaryHeader::Unprogrammed => Box::new(Unprogrammed {
-            data: data.to_vec(),
-        }) as Box<dyn EPC>,
+        EPCBinaryHeader::ADIVAR => OwnedEPCValue::ADIVar(adi::decode_adivar(body)?),
+        EPCBinaryHeader::CPI96 => OwnedEPCValue::CPI96(cpi::decode_cpi96(body)?),
+        EPCBinaryHeader::CPIVAR => OwnedEPCValue::CPIVar(cpi::decode_cpivar(body)?),
+        EPCBinaryHeader::GDTI96 => OwnedEPCValue::GDTI96(gdti::decode_gdti96(body)?),
+        EPCBinaryHeader::GDTI174 => OwnedEPCValue::GDTI174(gdti::decode_gdti174(body)?),
+        EPCBinaryHeader::GIAI96 => OwnedEPCValue::GIAI96(giai::decode_giai96(body)?),
+        EPCBinaryHeader::GID96 => OwnedEPCValue::GID96(gid::decode_gid96(body)?),
+        EPCBinaryHeader::GRAI96 => OwnedEPCValue::GRAI96(grai::decode_grai96_typed(body)?),
+        EPCBinaryHeader::GRAI170 => OwnedEPCValue::GRAI170(grai::decode_grai170(body)?),
+        EPCBinaryHeader::GSRN96 => OwnedEPCValue::GSRN96(gsrn::decode_gsrn96(body)?),
+        EPCBinaryHeader::ITIP110 => OwnedEPCValue::ITIP110(itip::decode_itip110(body)?),
+        EPCBinaryHeader::SGTIN96 => OwnedEPCValue::SGTIN96(sgtin::decode_sgtin96(body)?),
+        EPCBinaryHeader::SGTIN198 => OwnedEPCValue::SGTIN198(sgtin::decode_sgtin198(body)?),
+        EPCBinaryHeader::SGCN96 => OwnedEPCValue::SGCN96(sgcn::decode_sgcn96(body)?),
+        EPCBinaryHeader::SGLN96 => OwnedEPCValue::SGLN96(sgln::decode_sgln96(body)?),
+        EPCBinaryHeader::SSCC96 => OwnedEPCValue::SSCC96(sscc::decode_sscc96(body)?),
+        EPCBinaryHeader::USDoD96 => OwnedEPCValue::USDoD96(usdod::decode_usdod96(body)?),
+        EPCBinaryHeader::Unprogrammed => OwnedEPCValue::Unprogrammed(Unprogrammed {
+            data: body.to_vec(),
+        }),
         _unimplemented => {
-            return Err(Box::new(UnimplementedError()));
+            return Err(Gs1Error::Unimplemented(header));
         }
     })
 }
+
+/// Check whether a binary EPC code can be decoded, without allocating the result.
+///
+/// This is equivalent to `decode_binary(data).is_ok()`, and is intended for cheaply discarding
+/// malformed reads before a full decode. Tags with a valid header for a scheme which can't be
+/// decoded here are valid, as they're returned as `Raw`.
+///
+/// The SGTIN-198, GRAI-170 and GDTI-174 serials aren't read, as any characters are accepted
+/// there. The ADI-var and CPI-var schemes are fully decoded, as their variable-length strings
+/// have to be read to check them and to find the fields which follow.
+pub fn is_valid_epc(data: &[u8]) -> bool {
+    let (body, header) = match take_header(data) {
+        Ok(result) => result,
+        Err(_) => return false,
+    };
+    // Unimplemented schemes are decoded as Raw
+    if !is_implemented(header) {
+        return true;
+    }
+    if header.check_length(data).is_err() {
+        return false;
+    }
+
+    match header {
+        EPCBinaryHeader::ADIVAR => adi::decode_adivar(body).is_ok(),
+        EPCBinaryHeader::CPI96 => cpi::decode_cpi96(body).is_ok(),
+        EPCBinaryHeader::CPIVAR => cpi::decode_cpivar(body).is_ok(),
+        EPCBinaryHeader::GDTI96 => gdti::decode_gdti96(body).is_ok(),
+        EPCBinaryHeader::GDTI174 => gdti::validate_gdti174(body).is_ok(),
+        EPCBinaryHeader::GIAI96 => giai::decode_giai96(body).is_ok(),
+        EPCBinaryHeader::GID96 => gid::decode_gid96(body).is_ok(),
+        EPCBinaryHeader::GRAI96 => grai::decode_grai96_typed(body).is_ok(),
+        EPCBinaryHeader::GRAI170 => grai::validate_grai170(body).is_ok(),
+        EPCBinaryHeader::GSRN96 => gsrn::decode_gsrn96(body).is_ok(),
+        EPCBinaryHeader::ITIP110 => itip::decode_itip110(body).is_ok(),
+        EPCBinaryHeader::SGTIN96 => sgtin::decode_sgtin96(body).is_ok(),
+        EPCBinaryHeader::SGTIN198 => sgtin::validate_sgtin198(body).is_ok(),
+        EPCBinaryHeader::SGCN96 => sgcn::decode_sgcn96(body).is_ok(),
+        EPCBinaryHeader::SGLN96 => sgln::decode_sgln96(body).is_ok(),
+        EPCBinaryHeader::SSCC96 => sscc::decode_sscc96(body).is_ok(),
+        EPCBinaryHeader::USDoD96 => usdod::decode_usdod96(body).is_ok(),
+        EPCBinaryHeader::Unprogrammed => true,
+        _unimplemented => true,
+    }
+}
+
+fn raw(data: &[u8]) -> OwnedEPCValue {
+    OwnedEPCValue::Raw(Raw {
+        header: data[0],
+        data: data[1..].to_vec(),
+    })
+}
+
+/// Parse an EPC raw URI into a `Raw` EPC.
+///
+/// This is the inverse of `EPC::to_uri` for `Raw` EPCs, so tags which can't be decoded can be
+/// stored as URIs and converted back to binary with `EPC::to_binary`. Both the hexadecimal form
+/// (`urn:epc:raw:96.x3074257BF7194E4000001A85`) and the decimal form are accepted. The form with
+/// an ISO AFI isn't supported.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::{from_raw_uri, EPC};
+/// let raw = from_raw_uri("urn:epc:raw:32.x2E000001").unwrap();
+/// assert_eq!(raw.header, 0x2E);
+/// assert_eq!(raw.to_binary().unwrap(), [0x2E, 0x00, 0x00, 0x01]);
+/// ```
+// GS1 EPC TDS Section 12.4
+pub fn from_raw_uri(uri: &str) -> Result<Raw> {
+    let (length, payload) = uri
+        .strip_prefix("urn:epc:raw:")
+        .and_then(|rest| rest.split_once('.'))
+        .ok_or(Gs1Error::Parse)?;
+    let length = parse_decimal::<usize>(length)?;
+    // The length of an EPC is given by 5 bits of the PC word, in 16-bit words, so anything
+    // longer can't be stored on a tag
+    if length > 31 * 16 {
+        return Err(Gs1Error::Parse);
+    }
+
+    let data = match payload.strip_prefix('x') {
+        Some(digits) => {
+            if digits.len() != length.div_ceil(4) {
+                return Err(Gs1Error::Parse);
+            }
+            // Pad the payload to whole bytes
+            let mut digits = String::from(digits);
+            if digits.len() % 2 == 1 {
+                digits.push('0');
+            }
+            hex::decode(digits).map_err(|_| Gs1Error::Parse)?
+        }
+        None => decimal_to_bytes(payload, length)?,
+    };
+
+    match data.split_first() {
+        Some((&header, data)) => Ok(Raw {
+            header,
+            data: data.to_vec(),
+        }),
+        None => Err(Gs1Error::Parse),
+    }
+}
+
+// Convert a decimal raw URI payload to a big-endian buffer of `length` bits, padded to whole
+// bytes. The payload is the value of the bits as a single integer.
+fn decimal_to_bytes(payload: &str, length: usize) -> Result<Vec<u8>> {
+    if payload.is_empty() {
+        return Err(Gs1Error::Parse);
+    }
+    let mut value = alloc::vec![0u8; length.div_ceil(8)];
+    for c in payload.chars() {
+        let mut carry = c.to_digit(10).ok_or(Gs1Error::Parse)?;
+        for byte in value.iter_mut().rev() {
+            let product = *byte as u32 * 10 + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return Err(Gs1Error::Parse);
+        }
+    }
+
+    // The value is right-aligned in `length` bits, so shift it to start at the first byte
+    let shift = value.len() * 8 - length;
+    if shift > 0 {
+        if value[0] >> (8 - shift) != 0 {
+            return Err(Gs1Error::Parse);
+        }
+        for i in 0..value.len() {
+            let next = value.get(i + 1).copied().unwrap_or(0);
+            value[i] = (value[i] << shift) | (next >> (8 - shift));
+        }
+    }
+    Ok(value)
+}
+
+/// Parse an EPC tag URI into the corresponding EPC structure.
+///
+/// This is the inverse of `EPC::to_tag_uri`. Raw URIs are also accepted, and returned as `Raw`.
+///
+/// Example: `urn:epc:tag:sgtin-96:3.0614141.812345.6789`
+pub fn from_tag_uri(uri: &str) -> Result<Box<dyn EPC>> {
+    if uri.starts_with("urn:epc:raw:") {
+        return Ok(Box::new(from_raw_uri(uri)?));
+    }
+    let (scheme, fields) = uri
+        .strip_prefix("urn:epc:tag:")
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or(Gs1Error::Parse)?;
+    let fields: Vec<&str> = fields.split('.').collect();
+
+    match scheme {
+        "sgtin-96" => sgtin::sgtin96_from_tag_uri(&fields),
+        "sgtin-198" => sgtin::sgtin198_from_tag_uri(&fields),
+        "sscc-96" => sscc::sscc96_from_tag_uri(&fields),
+        "gid-96" => gid::gid96_from_uri(&fields),
+        "grai-96" => grai::grai96_from_tag_uri(&fields),
+        _ => Err(Gs1Error::Parse),
+    }
+}
+
+/// Parse an EPC pure identity URI into the corresponding EPC structure.
+///
+/// As the pure identity URI doesn't include the filter value, it's set to 0 ("all others") in
+/// the returned structure. SGTINs are returned as `SGTIN96` where the serial number can be
+/// represented in 96 bits, and as `SGTIN198` otherwise. Raw URIs are also accepted, and
+/// returned as `Raw`.
+///
+/// Example: `urn:epc:id:sgtin:0614141.812345.6789`
+pub fn from_uri(uri: &str) -> Result<Box<dyn EPC>> {
+    if uri.starts_with("urn:epc:raw:") {
+        return Ok(Box::new(from_raw_uri(uri)?));
+    }
+    let (scheme, fields) = uri
+        .strip_prefix("urn:epc:id:")
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or(Gs1Error::Parse)?;
+    let fields: Vec<&str> = fields.split('.').collect();
+
+    match scheme {
+        "sgtin" => sgtin::sgtin_from_uri(&fields),
+        "sscc" => sscc::sscc_from_uri(&fields),
+        "gid" => gid::gid96_from_uri(&fields),
+        "grai" => grai::grai_from_uri(&fields),
+        _ => Err(Gs1Error::Parse),
+    }
+}
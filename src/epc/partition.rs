@@ -0,0 +1,234 @@
+//! Partition tables
+//!
+//! Most EPC schemes divide a fixed number of bits between the GS1 company prefix and the field
+//! which follows it. The division is given by a 3-bit partition value, which indexes into a
+//! table specific to each scheme.
+use crate::epc::EPCBinaryHeader;
+use crate::error::{Gs1Error, Result};
+
+/// A partition table, indexed by partition value.
+///
+/// Each row holds the number of bits and decimal digits in the company prefix, followed by the
+/// number of bits and digits in the remainder of the partitioned field.
+pub(crate) struct PartitionTable([(u8, usize, u8, usize); 7]);
+
+impl PartitionTable {
+    /// Return `(company_bits, company_digits, remainder_bits, remainder_digits)` for a partition
+    /// value.
+    pub(crate) fn lookup(&self, partition: u8) -> Result<(u8, usize, u8, usize)> {
+        self.0
+            .get(partition as usize)
+            .copied()
+            .ok_or(Gs1Error::InvalidPartition(partition))
+    }
+
+    /// Return the partition value for a company prefix with `company_digits` digits.
+    ///
+    /// This is the inverse of the company digits column of the table.
+    pub(crate) fn partition(&self, company_digits: usize) -> Result<u8> {
+        self.0
+            .iter()
+            .position(|row| row.1 == company_digits)
+            .map(|partition| partition as u8)
+            .ok_or(Gs1Error::OutOfRange("company_digits"))
+    }
+
+    /// Return the number of digits in the company prefix and the remainder, for display.
+    ///
+    /// Without a valid partition the field widths are unknown, so the fields are unpadded.
+    pub(crate) fn digits(&self, partition: u8) -> (usize, usize) {
+        match self.lookup(partition) {
+            Ok((_, company_digits, _, remainder_digits)) => (company_digits, remainder_digits),
+            Err(_) => (0, 0),
+        }
+    }
+}
+
+/// SGTIN, where the remainder is the indicator digit and item reference.
+///
+/// GS1 EPC TDS Table 14-2
+pub(crate) const SGTIN: PartitionTable = PartitionTable([
+    (40, 12, 4, 1),
+    (37, 11, 7, 2),
+    (34, 10, 10, 3),
+    (30, 9, 14, 4),
+    (27, 8, 17, 5),
+    (24, 7, 20, 6),
+    (20, 6, 24, 7),
+]);
+
+/// SSCC, where the remainder is the extension digit and serial reference.
+///
+/// GS1 EPC TDS Table 14-5
+pub(crate) const SSCC: PartitionTable = PartitionTable([
+    (40, 12, 18, 5),
+    (37, 11, 21, 6),
+    (34, 10, 24, 7),
+    (30, 9, 28, 8),
+    (27, 8, 31, 9),
+    (24, 7, 34, 10),
+    (20, 6, 38, 11),
+]);
+
+/// GSRN, where the remainder is the service reference.
+///
+/// GS1 EPC TDS Table 14-6
+pub(crate) const GSRN: PartitionTable = PartitionTable([
+    (40, 12, 18, 5),
+    (37, 11, 21, 6),
+    (34, 10, 24, 7),
+    (30, 9, 28, 8),
+    (27, 8, 31, 9),
+    (24, 7, 34, 10),
+    (20, 6, 38, 11),
+]);
+
+/// SGLN, where the remainder is the location reference.
+///
+/// GS1 EPC TDS Table 14-8
+pub(crate) const SGLN: PartitionTable = PartitionTable([
+    (40, 12, 1, 0),
+    (37, 11, 4, 1),
+    (34, 10, 7, 2),
+    (30, 9, 11, 3),
+    (27, 8, 14, 4),
+    (24, 7, 17, 5),
+    (20, 6, 21, 6),
+]);
+
+/// GDTI, where the remainder is the document type.
+///
+/// GS1 EPC TDS Table 14-10
+pub(crate) const GDTI: PartitionTable = PartitionTable([
+    (40, 12, 1, 0),
+    (37, 11, 4, 1),
+    (34, 10, 7, 2),
+    (30, 9, 11, 3),
+    (27, 8, 14, 4),
+    (24, 7, 17, 5),
+    (20, 6, 21, 6),
+]);
+
+/// CPI-96, where the remainder is the component/part reference.
+///
+/// GS1 EPC TDS Table 14-19
+pub(crate) const CPI96: PartitionTable = PartitionTable([
+    (40, 12, 11, 3),
+    (37, 11, 14, 4),
+    (34, 10, 17, 5),
+    (30, 9, 21, 6),
+    (27, 8, 24, 7),
+    (24, 7, 27, 8),
+    (20, 6, 31, 9),
+]);
+
+/// CPI-var, where the remainder is the variable-length component/part reference. The remainder
+/// bits and digits are the maximum size of the reference, in bits including its terminator, and
+/// in characters.
+///
+/// GS1 EPC TDS Table 14-20
+pub(crate) const CPIVAR: PartitionTable = PartitionTable([
+    (40, 12, 114, 18),
+    (37, 11, 120, 19),
+    (34, 10, 126, 20),
+    (30, 9, 132, 21),
+    (27, 8, 138, 22),
+    (24, 7, 144, 23),
+    (20, 6, 150, 24),
+]);
+
+/// SGCN, where the remainder is the coupon reference.
+///
+/// GS1 EPC TDS Table 14-22
+pub(crate) const SGCN: PartitionTable = PartitionTable([
+    (40, 12, 1, 0),
+    (37, 11, 4, 1),
+    (34, 10, 7, 2),
+    (30, 9, 11, 3),
+    (27, 8, 14, 4),
+    (24, 7, 17, 5),
+    (20, 6, 21, 6),
+]);
+
+/// GIAI-96, where the remainder is the individual asset reference.
+///
+/// GS1 EPC TDS Table 14-12
+pub(crate) const GIAI96: PartitionTable = PartitionTable([
+    (40, 12, 42, 13),
+    (37, 11, 45, 14),
+    (34, 10, 48, 15),
+    (30, 9, 52, 16),
+    (27, 8, 55, 17),
+    (24, 7, 58, 18),
+    (20, 6, 62, 19),
+]);
+
+/// GRAI, where the remainder is the asset type.
+///
+/// GS1 EPC TDS Table 14-14
+pub(crate) const GRAI: PartitionTable = PartitionTable([
+    (40, 12, 4, 0),
+    (37, 11, 7, 1),
+    (34, 10, 10, 2),
+    (30, 9, 14, 3),
+    (27, 8, 17, 4),
+    (24, 7, 20, 5),
+    (20, 6, 24, 6),
+]);
+
+/// Return the partition table used by an EPC scheme, if it has one.
+pub(crate) fn for_scheme(scheme: EPCBinaryHeader) -> Option<&'static PartitionTable> {
+    use EPCBinaryHeader as H;
+    match scheme {
+        H::SGTIN96 | H::SGTIN198 | H::ITIP110 | H::ITIP212 => Some(&SGTIN),
+        H::SSCC96 => Some(&SSCC),
+        H::GSRN96 | H::GSRNP => Some(&GSRN),
+        H::SGLN96 | H::SGLN195 => Some(&SGLN),
+        H::GDTI96 | H::GDTI113 | H::GDTI174 => Some(&GDTI),
+        H::CPI96 => Some(&CPI96),
+        H::CPIVAR => Some(&CPIVAR),
+        H::SGCN96 => Some(&SGCN),
+        H::GIAI96 => Some(&GIAI96),
+        H::GRAI96 | H::GRAI170 => Some(&GRAI),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_partition_tables() {
+    for table in [SGTIN, SSCC, GSRN, SGLN, GDTI, CPI96, SGCN, GIAI96, GRAI].iter() {
+        // The company prefix and remainder together have a fixed size
+        let first = table.lookup(0).unwrap();
+        for partition in 0..7 {
+            let row = table.lookup(partition).unwrap();
+            assert_eq!(row.0 + row.2, first.0 + first.2);
+            assert_eq!(row.1 + row.3, first.1 + first.3);
+            assert_eq!(row.1, 12 - partition as usize);
+        }
+        assert_eq!(table.lookup(7), Err(Gs1Error::InvalidPartition(7)));
+        for company_digits in 6..=12 {
+            let partition = table.partition(company_digits).unwrap();
+            assert_eq!(table.lookup(partition).unwrap().1, company_digits);
+        }
+        assert_eq!(
+            table.partition(5),
+            Err(Gs1Error::OutOfRange("company_digits"))
+        );
+        assert_eq!(
+            table.partition(13),
+            Err(Gs1Error::OutOfRange("company_digits"))
+        );
+        assert_eq!(table.digits(7), (0, 0));
+    }
+
+    // The SSCC is 17 digits before its check digit, including the extension digit
+    for partition in 0..7 {
+        let row = SSCC.lookup(partition).unwrap();
+        assert_eq!(row.1 + row.3, 17);
+    }
+
+    // The size of the CPI-var part reference varies, so only the company prefix is checked
+    for partition in 0..7 {
+        assert_eq!(CPIVAR.lookup(partition).unwrap().1, 12 - partition as usize);
+    }
+}
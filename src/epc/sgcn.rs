@@ -0,0 +1,124 @@
+//! Serialised Global Coupon Number
+//!
+//! This is a combination of a company prefix assigned by GS1, a coupon reference assigned by
+//! that company, and a serial number which allows a coupon to be uniquely identified.
+use crate::checksum::gs1_checksum;
+use crate::epc::partition::SGCN as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::util::{invalid_field, read_digits};
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Serialised Global Coupon Number
+///
+/// This comprises a company prefix, a coupon reference, and a numeric serial number of up to 12
+/// digits, which may have leading zeros.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SGCN96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Coupon reference
+    pub coupon_reference: u64,
+    /// Serial component, as a string of digits
+    pub serial: String,
+}
+
+impl SGCN96 {
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, coupon_reference_width) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{:0coupon_reference_width$}.{}",
+            self.company_prefix, self.coupon_reference, self.serial
+        )
+    }
+}
+
+impl EPC for SGCN96 {
+    // GS1 EPC TDS section 6.3.10
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:sgcn:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:sgcn-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::SGCN96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::SGCN96(*self)
+    }
+}
+
+impl GS1 for SGCN96 {
+    fn to_gs1(&self) -> String {
+        let (company_width, coupon_reference_width) = PARTITIONS.digits(self.partition);
+        let element_string = format!(
+            "{:0company_width$}{:0coupon_reference_width$}",
+            self.company_prefix, self.coupon_reference
+        );
+        format!(
+            "({}) {}{}{}",
+            ApplicationIdentifier::GCN as u16,
+            element_string,
+            gs1_checksum(&element_string),
+            self.serial
+        )
+    }
+}
+
+// GS1 EPC TDS Section 14.6.10
+pub(super) fn decode_sgcn96(data: &[u8]) -> Result<SGCN96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, coupon_reference_bits, coupon_reference_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let coupon_reference = read_digits(
+        &mut reader,
+        coupon_reference_bits,
+        coupon_reference_digits,
+        "coupon_reference",
+    )?;
+
+    // The serial is encoded as an integer with a leading 1 digit, so that leading zeros are
+    // preserved.
+    // GS1 EPC TDS Section 14.3.11
+    let position = reader.position();
+    let serial = reader.read_u64(41)?.to_string();
+    let serial = match serial.strip_prefix('1') {
+        Some(digits) if digits.len() <= 12 => digits.to_string(),
+        _ => return Err(invalid_field("serial", position)),
+    };
+
+    Ok(SGCN96 {
+        filter,
+        partition,
+        company_prefix,
+        coupon_reference,
+        serial,
+    })
+}
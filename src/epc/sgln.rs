@@ -0,0 +1,120 @@
+//! Global Location Number with or without extension
+//!
+//! This is a combination of a company prefix assigned by GS1, a location reference assigned by
+//! that company, and an optional extension which identifies a sub-location.
+use crate::checksum::gs1_checksum;
+use crate::epc::partition::SGLN as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::Result;
+use crate::gln::GLN;
+use crate::util::{optional_field, read_digits};
+use crate::{ApplicationIdentifier, GS1};
+use alloc::{boxed::Box, format, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit Global Location Number with Extension
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SGLN96 {
+    /// Filter value to allow RFID readers to select the type of tag to read.
+    pub filter: u8,
+    /// Partition value, which determines the number of digits in the company prefix
+    pub partition: u8,
+    /// GS1 Company Prefix
+    pub company_prefix: u64,
+    /// Location reference
+    pub location: u64,
+    /// Extension, where zero indicates that no extension is present
+    pub extension: u64,
+}
+
+impl SGLN96 {
+    /// Return the GLN of the location, without the extension.
+    pub fn gln(&self) -> GLN {
+        GLN {
+            company: self.company_prefix,
+            company_digits: PARTITIONS.digits(self.partition).0,
+            location: self.location,
+        }
+    }
+
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, location_width) = PARTITIONS.digits(self.partition);
+        write!(
+            w,
+            "{:0company_width$}.{:0location_width$}.{}",
+            self.company_prefix, self.location, self.extension
+        )
+    }
+}
+
+impl EPC for SGLN96 {
+    // GS1 EPC TDS section 6.3.3
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:sgln:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:sgln-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::SGLN96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::SGLN96(*self)
+    }
+}
+
+impl GS1 for SGLN96 {
+    fn to_gs1(&self) -> String {
+        let (company_width, location_width) = PARTITIONS.digits(self.partition);
+        let gln = format!(
+            "{:0company_width$}{:0location_width$}",
+            self.company_prefix, self.location
+        );
+        let mut element_string = format!(
+            "({}) {}{}",
+            ApplicationIdentifier::LocationGLN as u16,
+            gln,
+            gs1_checksum(&gln)
+        );
+        if let Some(extension) = optional_field(self.extension) {
+            element_string += &format!(
+                " ({}) {}",
+                ApplicationIdentifier::GLNExtension as u16,
+                extension
+            );
+        }
+        element_string
+    }
+}
+
+// GS1 EPC TDS Section 14.5.3
+pub(super) fn decode_sgln96(data: &[u8]) -> Result<SGLN96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(3)?;
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, location_bits, location_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company_prefix = read_digits(&mut reader, company_bits, company_digits, "company_prefix")?;
+    let location = read_digits(&mut reader, location_bits, location_digits, "location")?;
+    let extension = reader.read_u64(41)?;
+
+    Ok(SGLN96 {
+        filter,
+        partition,
+        company_prefix,
+        location,
+        extension,
+    })
+}
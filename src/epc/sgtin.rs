@@ -2,17 +2,98 @@
 //!
 //! This is a combination of a GTIN and a serial number which allows an item to be uniquely
 //! identified.
-use crate::epc::{EPCValue, EPC};
-use crate::error::{ParseError, Result};
-use crate::util::{extract_indicator, read_string, uri_encode, zero_pad};
+use crate::digital_link::compress_gtin_serial;
+use crate::epc::partition::SGTIN as PARTITIONS;
+use crate::epc::{EPCBinaryHeader, EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use crate::util::{
+    extract_indicator, invalid_field, is_cset82, parse_decimal, parse_filter, parse_serial,
+    read_digits, read_string, uri_decode, uri_encode, write_string, BitWriter,
+};
 use crate::{ApplicationIdentifier, GS1, GTIN};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use bitreader::BitReader;
+use core::fmt;
+
+/// A conformance problem found in an SGTIN.
+///
+/// SGTINs with these problems may still be decodable, but they aren't encoded according to the
+/// standard.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum ConformanceIssue {
+    /// The filter value doesn't fit in the 3-bit filter field
+    InvalidFilter(u8),
+    /// The filter value is reserved for future use (GS1 EPC TDS Table 10-1)
+    ReservedFilter(u8),
+    /// The serial number can't be canonically encoded in this scheme
+    NonCanonicalSerial,
+    /// The number of company prefix digits doesn't correspond to a partition value
+    InvalidCompanyDigits(usize),
+    /// The company prefix has more digits than the partition allows
+    CompanyOverflow,
+    /// The item reference has more digits than the partition allows
+    ItemOverflow,
+    /// The indicator digit isn't a single decimal digit
+    InvalidIndicator(u8),
+}
+
+// Check the filter and GTIN fields common to both SGTIN schemes.
+fn conformance_issues(filter: u8, gtin: &GTIN) -> Vec<ConformanceIssue> {
+    let mut issues = Vec::new();
+
+    match filter {
+        3 | 5 => issues.push(ConformanceIssue::ReservedFilter(filter)),
+        8.. => issues.push(ConformanceIssue::InvalidFilter(filter)),
+        _ => {}
+    }
+
+    if (6..=12).contains(&gtin.company_digits) {
+        if gtin.company >= 10u64.pow(gtin.company_digits as u32) {
+            issues.push(ConformanceIssue::CompanyOverflow);
+        }
+        if gtin.item >= 10u64.pow(12 - gtin.company_digits as u32) {
+            issues.push(ConformanceIssue::ItemOverflow);
+        }
+    } else {
+        issues.push(ConformanceIssue::InvalidCompanyDigits(gtin.company_digits));
+    }
+
+    if gtin.indicator > 9 {
+        issues.push(ConformanceIssue::InvalidIndicator(gtin.indicator));
+    }
+
+    issues
+}
+
+// Write the company prefix and the indicator and item reference fields of an SGTIN URI, which
+// are zero-padded to the width given by the company prefix length.
+// GS1 EPC TDS Section 6.3.1
+pub(super) fn write_gtin_fields(w: &mut dyn fmt::Write, gtin: &GTIN) -> fmt::Result {
+    write!(
+        w,
+        "{:0company_width$}.{}",
+        gtin.company,
+        gtin.indicator,
+        company_width = gtin.company_digits
+    )?;
+    // With a 12-digit company prefix, the item reference is empty
+    match 12usize.saturating_sub(gtin.company_digits) {
+        0 => Ok(()),
+        item_width => write!(w, "{:0item_width$}", gtin.item),
+    }
+}
 
 /// 96-bit Serialised Global Trade Item Number
 ///
 /// This comprises a GTIN, a filter value (which is used by RFID readers), and a numeric serial
 /// number.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SGTIN96 {
     /// Filter value to allow RFID readers to select the type of tag to read.
     pub filter: u8,
@@ -24,30 +105,78 @@ pub struct SGTIN96 {
 
 impl EPC for SGTIN96 {
     // GS1 EPC TDS section 6.3.1
-    fn to_uri(&self) -> String {
-        format!(
-            "urn:epc:id:sgtin:{}.{}{}.{}",
-            zero_pad(self.gtin.company.to_string(), self.gtin.company_digits),
-            self.gtin.indicator,
-            zero_pad(self.gtin.item.to_string(), 12 - self.gtin.company_digits),
-            self.serial
-        )
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:sgtin:")?;
+        write_gtin_fields(w, &self.gtin)?;
+        write!(w, ".{}", self.serial)
     }
 
-    fn to_tag_uri(&self) -> String {
-        format!(
-            "urn:epc:tag:sgtin-96:{}.{}.{}{}.{}",
-            self.filter,
-            zero_pad(self.gtin.company.to_string(), self.gtin.company_digits),
-            self.gtin.indicator,
-            zero_pad(self.gtin.item.to_string(), 12 - self.gtin.company_digits),
-            self.serial
-        )
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:sgtin-96:{}.", self.filter)?;
+        write_gtin_fields(w, &self.gtin)?;
+        write!(w, ".{}", self.serial)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
     }
 
-    fn get_value(&self) -> EPCValue {
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::SGTIN96(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::SGTIN96(*self)
+    }
+}
+
+impl SGTIN96 {
+    /// Create an SGTIN-96 from a GTIN and a serial number.
+    ///
+    /// Returns `Gs1Error::OutOfRange` if the serial number doesn't fit in 38 bits, or if the
+    /// filter doesn't fit in 3 bits.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::{sgtin::SGTIN96, EPC};
+    /// # use gs1::GTIN;
+    /// let gtin: GTIN = "80614141123458".parse().unwrap();
+    /// let sgtin = SGTIN96::from_gtin(gtin, 6789, 3).unwrap();
+    /// assert_eq!(sgtin.to_tag_uri(), "urn:epc:tag:sgtin-96:3.0614141.812345.6789");
+    /// ```
+    pub fn from_gtin(gtin: GTIN, serial: u64, filter: u8) -> Result<SGTIN96> {
+        if serial >= 1 << 38 {
+            return Err(Gs1Error::OutOfRange("serial"));
+        }
+        if filter > 7 {
+            return Err(Gs1Error::OutOfRange("filter"));
+        }
+        Ok(SGTIN96 {
+            filter,
+            gtin,
+            serial,
+        })
+    }
+
+    /// Return the compressed GS1 Digital Link URI for this SGTIN, using the provided domain.
+    ///
+    /// Example: `https://id.gs1.org/AZKi4n23BEII1Cg`
+    pub fn to_digital_link_compressed(&self, domain: &str) -> Result<String> {
+        compress_gtin_serial(domain, &self.gtin.to_gtin14(), &self.serial.to_string())
+    }
+
+    /// Check this SGTIN for conformance with the standard, returning a list of any problems
+    /// found.
+    ///
+    /// This checks the filter value, that the serial number fits in 38 bits, and that the GTIN
+    /// fields are consistent with a valid partition value.
+    pub fn conformance_check(&self) -> Vec<ConformanceIssue> {
+        let mut issues = conformance_issues(self.filter, &self.gtin);
+        if self.serial >= 1 << 38 {
+            issues.push(ConformanceIssue::NonCanonicalSerial);
+        }
+        issues
+    }
 }
 
 impl GS1 for SGTIN96 {
@@ -66,7 +195,8 @@ impl GS1 for SGTIN96 {
 ///
 /// This comprises a GTIN, a filter value (which is used by RFID readers), and an
 /// alphanumeric serial number which is encoded using 7-bit ASCII.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SGTIN198 {
     /// Filter value to allow RFID readers to select tags to read
     pub filter: u8,
@@ -78,30 +208,83 @@ pub struct SGTIN198 {
 
 impl EPC for SGTIN198 {
     // GS1 EPC TDS section 6.3.1
-    fn to_uri(&self) -> String {
-        format!(
-            "urn:epc:id:sgtin:{}.{}{}.{}",
-            zero_pad(self.gtin.company.to_string(), self.gtin.company_digits),
-            self.gtin.indicator,
-            zero_pad(self.gtin.item.to_string(), 12 - self.gtin.company_digits),
-            uri_encode(self.serial.to_string())
-        )
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:sgtin:")?;
+        write_gtin_fields(w, &self.gtin)?;
+        write!(w, ".{}", uri_encode(&self.serial))
     }
 
-    fn to_tag_uri(&self) -> String {
-        format!(
-            "urn:epc:tag:sgtin-198:{}.{}.{}{}.{}",
-            self.filter,
-            zero_pad(self.gtin.company.to_string(), self.gtin.company_digits),
-            self.gtin.indicator,
-            zero_pad(self.gtin.item.to_string(), 12 - self.gtin.company_digits),
-            uri_encode(self.serial.to_string())
-        )
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:sgtin-198:{}.", self.filter)?;
+        write_gtin_fields(w, &self.gtin)?;
+        write!(w, ".{}", uri_encode(&self.serial))
     }
 
-    fn get_value(&self) -> EPCValue {
+    fn bit_length(&self) -> usize {
+        198
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::SGTIN198(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::SGTIN198(*self)
+    }
+}
+
+impl SGTIN198 {
+    /// Create an SGTIN-198 from a GTIN and an alphanumeric serial number.
+    ///
+    /// Returns `Gs1Error::InvalidCharacter` if the serial number contains a character outside
+    /// the GS1 AI encodable character set 82, `Gs1Error::OutOfRange("serial")` if it's longer than
+    /// 20 characters, and `Gs1Error::OutOfRange("filter")` if the filter doesn't fit in 3 bits.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::{sgtin::SGTIN198, EPC};
+    /// # use gs1::GTIN;
+    /// let gtin: GTIN = "80614141123458".parse().unwrap();
+    /// let sgtin = SGTIN198::new(3, gtin, "32a/b".to_string()).unwrap();
+    /// assert_eq!(sgtin.to_tag_uri(), "urn:epc:tag:sgtin-198:3.0614141.812345.32a%2Fb");
+    /// ```
+    pub fn new(filter: u8, gtin: GTIN, serial: String) -> Result<SGTIN198> {
+        if let Some(c) = serial.chars().find(|&c| !is_cset82(c)) {
+            return Err(Gs1Error::InvalidCharacter(c));
+        }
+        if serial.len() > 20 {
+            return Err(Gs1Error::OutOfRange("serial"));
+        }
+        if filter > 7 {
+            return Err(Gs1Error::OutOfRange("filter"));
+        }
+        Ok(SGTIN198 {
+            filter,
+            gtin,
+            serial,
+        })
+    }
+
+    /// Return the compressed GS1 Digital Link URI for this SGTIN, using the provided domain.
+    ///
+    /// Example: `https://id.gs1.org/AZKi4n23BEII1Cg`
+    pub fn to_digital_link_compressed(&self, domain: &str) -> Result<String> {
+        compress_gtin_serial(domain, &self.gtin.to_gtin14(), &self.serial)
+    }
+
+    /// Check this SGTIN for conformance with the standard, returning a list of any problems
+    /// found.
+    ///
+    /// This checks the filter value, that the serial number is at most 20 characters from the
+    /// permitted character set, and that the GTIN fields are consistent with a valid partition
+    /// value.
+    pub fn conformance_check(&self) -> Vec<ConformanceIssue> {
+        let mut issues = conformance_issues(self.filter, &self.gtin);
+        if self.serial.chars().count() > 20 || !self.serial.chars().all(is_cset82) {
+            issues.push(ConformanceIssue::NonCanonicalSerial);
+        }
+        issues
+    }
 }
 
 impl GS1 for SGTIN198 {
@@ -116,77 +299,204 @@ impl GS1 for SGTIN198 {
     }
 }
 
-// Calculate the number of digits in the decimal representation of a SGTIN
-// company code from the partition ID.
-// GS1 EPC TDS Table 14-2
-fn company_digits(partition: u8) -> usize {
-    12 - partition as usize
-}
-
-fn item_digits(partition: u8) -> usize {
-    13 - company_digits(partition)
-}
+// Read the partition, company prefix, and indicator/item reference fields which are common to
+// the SGTIN and ITIP schemes.
+pub(super) fn read_gtin(reader: &mut BitReader) -> Result<GTIN> {
+    let partition = reader.read_u8(3)?;
+    let (company_bits, company_digits, item_bits, item_digits) = PARTITIONS.lookup(partition)?;
+    let company = read_digits(reader, company_bits, company_digits, "company")?;
+    let position = reader.position();
+    let item = reader.read_u64(item_bits)?;
+    let (item, indicator) =
+        extract_indicator(item, item_digits).map_err(|_| invalid_field("item", position))?;
 
-// GS1 EPC TDS Table 14-2
-fn partition_bits(partition: u8) -> Result<(u8, u8)> {
-    Ok(match partition {
-        0 => (40, 4),
-        1 => (37, 7),
-        2 => (34, 10),
-        3 => (30, 14),
-        4 => (27, 17),
-        5 => (24, 20),
-        6 => (20, 24),
-        _ => {
-            return Err(Box::new(ParseError()));
-        }
+    Ok(GTIN {
+        company,
+        company_digits,
+        item,
+        indicator,
     })
 }
 
 // GS1 EPC TDC Section 14.5.1
-pub(super) fn decode_sgtin96(data: &[u8]) -> Result<Box<dyn EPC>> {
+pub(super) fn decode_sgtin96(data: &[u8]) -> Result<SGTIN96> {
     let mut reader = BitReader::new(data);
 
     let filter = reader.read_u8(3)?;
-    let partition = reader.read_u8(3)?;
-    let (company_bits, item_bits) = partition_bits(partition)?;
-    let company = reader.read_u64(company_bits)?;
-    let item = reader.read_u64(item_bits)?;
-    let (item, indicator) = extract_indicator(item, item_digits(partition))?;
+    let gtin = read_gtin(&mut reader)?;
     let serial = reader.read_u64(38)?;
 
-    Ok(Box::new(SGTIN96 {
+    Ok(SGTIN96 {
         filter,
-        gtin: GTIN {
-            company,
-            company_digits: company_digits(partition),
-            item,
-            indicator,
-        },
+        gtin,
         serial,
-    }))
+    })
+}
+
+// Check the fields of an SGTIN-198 without decoding it. Any 7-bit characters are accepted in
+// the serial, so it doesn't need to be read.
+pub(super) fn validate_sgtin198(data: &[u8]) -> Result<()> {
+    let mut reader = BitReader::new(data);
+    reader.skip(3)?;
+    read_gtin(&mut reader)?;
+    Ok(())
 }
 
 // GS1 EPC TDC Section 14.5.1.2
-pub(super) fn decode_sgtin198(data: &[u8]) -> Result<Box<dyn EPC>> {
+pub(super) fn decode_sgtin198(data: &[u8]) -> Result<SGTIN198> {
     let mut reader = BitReader::new(data);
 
     let filter = reader.read_u8(3)?;
-    let partition = reader.read_u8(3)?;
-    let (company_bits, item_bits) = partition_bits(partition)?;
-    let company = reader.read_u64(company_bits)?;
-    let item = reader.read_u64(item_bits)?;
-    let (item, indicator) = extract_indicator(item, item_digits(partition))?;
+    let gtin = read_gtin(&mut reader)?;
     let serial = read_string(reader, 140)?;
 
-    Ok(Box::new(SGTIN198 {
+    Ok(SGTIN198 {
         filter,
-        gtin: GTIN {
-            company,
-            company_digits: company_digits(partition),
-            item,
-            indicator,
-        },
+        gtin,
         serial,
-    }))
+    })
+}
+
+// Reconstitute a GTIN from the company prefix and indicator/item reference fields of an SGTIN URI.
+// The number of company digits is significant, so it's taken from the length of the field.
+fn parse_gtin(company: &str, item: &str) -> Result<GTIN> {
+    let company_digits = company.len();
+    if !(6..=12).contains(&company_digits) || company_digits + item.len() != 13 {
+        return Err(Gs1Error::Parse);
+    }
+    // Check the item is ASCII before splitting off the indicator digit
+    if !item.is_ascii() {
+        return Err(Gs1Error::Parse);
+    }
+
+    let indicator = parse_decimal::<u8>(&item[..1])?;
+    let item = match &item[1..] {
+        "" => 0,
+        item => parse_decimal::<u64>(item)?,
+    };
+
+    Ok(GTIN {
+        company: parse_decimal::<u64>(company)?,
+        company_digits,
+        item,
+        indicator,
+    })
+}
+
+// GS1 EPC TDS Section 12.4
+pub(super) fn sgtin96_from_tag_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [filter, company, item, serial] => Ok(Box::new(SGTIN96 {
+            filter: parse_filter(filter)?,
+            gtin: parse_gtin(company, item)?,
+            serial: parse_serial(serial, 38)?,
+        })),
+        _ => Err(Gs1Error::Parse),
+    }
+}
+
+// GS1 EPC TDS Section 12.4
+pub(super) fn sgtin198_from_tag_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        // Periods aren't escaped in the URI form, so the serial may span several fields
+        [filter, company, item, serial @ ..] if !serial.is_empty() => Ok(Box::new(SGTIN198 {
+            filter: parse_filter(filter)?,
+            gtin: parse_gtin(company, item)?,
+            serial: uri_decode(&serial.join("."))?,
+        })),
+        _ => Err(Gs1Error::Parse),
+    }
+}
+
+// GS1 EPC TDS Section 6.3.1
+pub(super) fn sgtin_from_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    let (company, item, serial) = match fields {
+        // Periods aren't escaped in the URI form, so the serial may span several fields
+        [company, item, serial @ ..] if !serial.is_empty() => (company, item, serial.join(".")),
+        _ => return Err(Gs1Error::Parse),
+    };
+    let gtin = parse_gtin(company, item)?;
+    let serial = uri_decode(&serial)?;
+
+    // SGTIN-96 can only represent numeric serials with no leading zeros which fit in 38 bits.
+    let numeric_serial = parse_serial(&serial, 38).ok();
+
+    Ok(match numeric_serial {
+        Some(serial) => Box::new(SGTIN96 {
+            filter: 0,
+            gtin,
+            serial,
+        }),
+        None => Box::new(SGTIN198 {
+            filter: 0,
+            gtin,
+            serial,
+        }),
+    })
+}
+
+// Write the filter, partition, company prefix and item reference fields common to both SGTIN
+// schemes.
+fn write_gtin(writer: &mut BitWriter, filter: u8, gtin: &GTIN) -> Result<()> {
+    if filter > 7 {
+        return Err(Gs1Error::OutOfRange("filter"));
+    }
+    let partition = PARTITIONS.partition(gtin.company_digits)?;
+    let (company_bits, _, item_bits, item_digits) = PARTITIONS.lookup(partition)?;
+    if gtin.company >= 10u64.pow(gtin.company_digits as u32) {
+        return Err(Gs1Error::OutOfRange("company"));
+    }
+    if gtin.item >= 10u64.pow(item_digits as u32 - 1) {
+        return Err(Gs1Error::OutOfRange("item"));
+    }
+    if gtin.indicator > 9 {
+        return Err(Gs1Error::OutOfRange("indicator"));
+    }
+
+    // The indicator digit is stored as the first digit of the item reference
+    let item = gtin.indicator as u64 * 10u64.pow(item_digits as u32 - 1) + gtin.item;
+
+    writer.write_u64(filter as u64, 3);
+    writer.write_u64(partition as u64, 3);
+    writer.write_u64(gtin.company, company_bits);
+    writer.write_u64(item, item_bits);
+    Ok(())
+}
+
+/// Encode an SGTIN-96 to its binary form.
+///
+/// The serial number must fit in 38 bits.
+///
+/// Reference: GS1 EPC TDS Section 14.5.1.1
+pub fn encode_sgtin96(sgtin: &SGTIN96) -> Result<Vec<u8>> {
+    if sgtin.serial >= 1 << 38 {
+        return Err(Gs1Error::OutOfRange("serial"));
+    }
+
+    let mut writer = BitWriter::new();
+    writer.write_u64(EPCBinaryHeader::SGTIN96 as u64, 8);
+    write_gtin(&mut writer, sgtin.filter, &sgtin.gtin)?;
+    writer.write_u64(sgtin.serial, 38);
+    Ok(writer.into_bytes())
+}
+
+/// Encode an SGTIN-198 to its binary form.
+///
+/// The 198 encoded bits are padded with zeros to 25 bytes. The serial number must be at most 20
+/// characters from the GS1 AI encodable character set 82.
+///
+/// Reference: GS1 EPC TDS Section 14.5.1.2
+pub fn encode_sgtin198(sgtin: &SGTIN198) -> Result<Vec<u8>> {
+    if sgtin.serial.len() > 20 {
+        return Err(Gs1Error::OutOfRange("serial"));
+    }
+    if let Some(c) = sgtin.serial.chars().find(|&c| !is_cset82(c)) {
+        return Err(Gs1Error::InvalidCharacter(c));
+    }
+
+    let mut writer = BitWriter::new();
+    writer.write_u64(EPCBinaryHeader::SGTIN198 as u64, 8);
+    write_gtin(&mut writer, sgtin.filter, &sgtin.gtin)?;
+    write_string(&mut writer, &sgtin.serial, 140);
+    Ok(writer.into_bytes())
 }
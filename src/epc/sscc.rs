@@ -1,13 +1,17 @@
 //! Serial Shipping Container Code
-use crate::checksum::gs1_checksum;
-use crate::epc::{EPCValue, EPC};
-use crate::error::{ParseError, Result};
-use crate::util::{extract_indicator, zero_pad};
-use crate::{ApplicationIdentifier, GS1};
+use crate::checksum::{gs1_checksum, gs1_checksum_valid};
+use crate::epc::partition::SSCC as PARTITIONS;
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use crate::util::{extract_indicator, invalid_field, parse_decimal, parse_filter, read_digits};
+use crate::{ApplicationIdentifier, GS1, GTIN};
+use alloc::{boxed::Box, format, string::String};
 use bitreader::BitReader;
+use core::fmt;
 
 /// 96-bit Serial Shipping Container Code
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SSCC96 {
     /// Filter value to allow RFID readers to select the type of tag to read.
     pub filter: u8,
@@ -17,40 +21,122 @@ pub struct SSCC96 {
     pub serial: u64,
 }
 
-impl EPC for SSCC96 {
-    // GS1 EPC TDS section 6.3.1
-    fn to_uri(&self) -> String {
+impl SSCC96 {
+    /// Parse an 18-digit SSCC, as printed on a logistic label, into an SSCC-96.
+    ///
+    /// As the company prefix length can't be determined from the SSCC alone, it must be supplied.
+    /// The filter value is set to 0 ("all others"). Returns `Gs1Error::InvalidChecksum` if the
+    /// check digit is incorrect.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::sscc::SSCC96;
+    /// # use gs1::epc::EPC;
+    /// let sscc = SSCC96::from_sscc("106141412345678908", 7).unwrap();
+    /// assert_eq!(sscc.to_uri(), "urn:epc:id:sscc:0614141.1234567890");
+    /// ```
+    pub fn from_sscc(sscc: &str, company_digits: usize) -> Result<SSCC96> {
+        if sscc.len() != 18 || !sscc.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Gs1Error::Parse);
+        }
+        if !gs1_checksum_valid(sscc) {
+            return Err(Gs1Error::InvalidChecksum);
+        }
+        if !(6..=12).contains(&company_digits) {
+            return Err(Gs1Error::OutOfRange("company_digits"));
+        }
+
+        // The extension digit comes before the company prefix in the SSCC, but is stored with
+        // the serial reference in the EPC.
+        let (extension, rest) = sscc.split_at(1);
+        let (company, serial) = rest[..16].split_at(company_digits);
+        parse_sscc(0, company, &format!("{}{}", extension, serial))
+    }
+
+    /// Return the 18-digit SSCC, including the check digit, as printed on a logistic label.
+    ///
+    /// This is the inverse of `from_sscc`. Returns `Gs1Error::InvalidPartition` if the partition
+    /// value is invalid, and `Gs1Error::OutOfRange` if a field doesn't fit in the number of digits
+    /// the partition allows, as the SSCC would then have the wrong length.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::epc::sscc::SSCC96;
+    /// let sscc = SSCC96::from_sscc("106141412345678908", 7).unwrap();
+    /// assert_eq!(sscc.to_sscc().unwrap(), "106141412345678908");
+    /// ```
+    pub fn to_sscc(&self) -> Result<String> {
+        let (_, company_digits, _, serial_digits) = PARTITIONS.lookup(self.partition)?;
+        if self.indicator > 9 {
+            return Err(Gs1Error::OutOfRange("indicator"));
+        }
+        if self.company >= 10u64.pow(company_digits as u32) {
+            return Err(Gs1Error::OutOfRange("company"));
+        }
+        // The serial field of the partition includes the extension digit
+        if self.serial >= 10u64.pow(serial_digits as u32 - 1) {
+            return Err(Gs1Error::OutOfRange("serial"));
+        }
+        let body = self.element_string_body();
+        Ok(format!("{}{}", body, gs1_checksum(&body)))
+    }
+
+    // The digits of the SSCC before the check digit, which number 17 if the fields fit their
+    // partition.
+    fn element_string_body(&self) -> String {
+        let (company_width, serial_width) = self.field_widths();
         format!(
-            "urn:epc:id:sscc:{}.{}{}",
-            zero_pad(self.company.to_string(), company_digits(self.partition)),
-            self.indicator,
-            zero_pad(self.serial.to_string(), item_digits(self.partition) - 1)
+            "{}{:0company_width$}{:0serial_width$}",
+            self.indicator, self.company, self.serial
         )
     }
 
-    fn to_tag_uri(&self) -> String {
-        format!(
-            "urn:epc:tag:sscc-96:{}.{}.{}{}",
-            self.filter,
-            zero_pad(self.company.to_string(), company_digits(self.partition)),
-            self.indicator,
-            zero_pad(self.serial.to_string(), item_digits(self.partition) - 1)
+    // The number of digits in the company prefix and serial reference (excluding the extension
+    // digit), which are zero-padded to these widths.
+    fn field_widths(&self) -> (usize, usize) {
+        let (company_digits, serial_digits) = PARTITIONS.digits(self.partition);
+        (company_digits, serial_digits.saturating_sub(1))
+    }
+
+    // Write the company prefix, extension digit and serial reference URI fields.
+    fn write_fields(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        let (company_width, serial_width) = self.field_widths();
+        write!(
+            w,
+            "{:0company_width$}.{}{:0serial_width$}",
+            self.company, self.indicator, self.serial
         )
     }
+}
+
+impl EPC for SSCC96 {
+    // GS1 EPC TDS section 6.3.1
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:id:sscc:")?;
+        self.write_fields(w)
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "urn:epc:tag:sscc-96:{}.", self.filter)?;
+        self.write_fields(w)
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
 
-    fn get_value(&self) -> EPCValue {
+    fn get_value(&self) -> EPCValue<'_> {
         EPCValue::SSCC96(self)
     }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::SSCC96(*self)
+    }
 }
 
 impl GS1 for SSCC96 {
     fn to_gs1(&self) -> String {
-        let element_string = format!(
-            "{}{}{}",
-            self.indicator,
-            zero_pad(self.company.to_string(), company_digits(self.partition)),
-            zero_pad(self.serial.to_string(), item_digits(self.partition) - 1)
-        );
+        let element_string = self.element_string_body();
         format!(
             "({:0>2}) {}{}",
             ApplicationIdentifier::SSCC as u16,
@@ -60,49 +146,113 @@ impl GS1 for SSCC96 {
     }
 }
 
-// Calculate the number of digits in the decimal representation of a SGTIN
-// company code from the partition ID.
-// GS1 EPC TDS Table 14-5
-fn company_digits(partition: u8) -> usize {
-    12 - partition as usize
+/// A logistic unit identified by an SSCC, which may contain a number of identical trade items.
+///
+/// This produces the combined element string used on logistic labels for homogeneous units, for
+/// example: `(00) 106141412345678908 (02) 80614141123458 (37) 24`
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogisticUnit {
+    sscc: SSCC96,
+    content: Option<(GTIN, u32)>,
 }
 
-fn item_digits(partition: u8) -> usize {
-    17 - company_digits(partition)
+impl LogisticUnit {
+    pub fn new(sscc: SSCC96) -> LogisticUnit {
+        LogisticUnit {
+            sscc,
+            content: None,
+        }
+    }
+
+    /// Set the GTIN of the trade items contained in this logistic unit, and their quantity.
+    ///
+    /// The quantity is limited to 8 digits.
+    pub fn with_content(mut self, gtin: GTIN, quantity: u32) -> Result<LogisticUnit> {
+        if quantity > 99_999_999 {
+            return Err(Gs1Error::Parse);
+        }
+        self.content = Some((gtin, quantity));
+        Ok(self)
+    }
 }
 
-// GS1 EPC TDS Table 14-5
-fn partition_bits(partition: u8) -> Result<(u8, u8)> {
-    Ok(match partition {
-        0 => (40, 18),
-        1 => (37, 21),
-        2 => (34, 24),
-        3 => (30, 28),
-        4 => (27, 31),
-        5 => (24, 34),
-        6 => (20, 48),
-        _ => {
-            return Err(Box::new(ParseError()));
+impl GS1 for LogisticUnit {
+    fn to_gs1(&self) -> String {
+        let sscc_gs1 = self.sscc.to_gs1();
+        match &self.content {
+            Some((gtin, quantity)) => format!(
+                "{} ({:0>2}) {} ({:0>2}) {}",
+                sscc_gs1,
+                ApplicationIdentifier::GTINContent as u16,
+                gtin.to_gtin14(),
+                ApplicationIdentifier::ContentCount as u16,
+                quantity
+            ),
+            None => sscc_gs1,
         }
-    })
+    }
 }
 
 // GS1 EPC TDC Section 14.5.2
-pub(super) fn decode_sscc96(data: &[u8]) -> Result<Box<dyn EPC>> {
+pub(super) fn decode_sscc96(data: &[u8]) -> Result<SSCC96> {
     let mut reader = BitReader::new(data);
 
     let filter = reader.read_u8(3)?;
     let partition = reader.read_u8(3)?;
-    let (company_bits, serial_bits) = partition_bits(partition)?;
-    let company = reader.read_u64(company_bits)?;
+    let (company_bits, company_digits, serial_bits, serial_digits) =
+        PARTITIONS.lookup(partition)?;
+    let company = read_digits(&mut reader, company_bits, company_digits, "company")?;
+    let position = reader.position();
     let serial = reader.read_u64(serial_bits)?;
-    let (serial, indicator) = extract_indicator(serial, item_digits(partition))?;
+    let (serial, indicator) =
+        extract_indicator(serial, serial_digits).map_err(|_| invalid_field("serial", position))?;
 
-    Ok(Box::new(SSCC96 {
+    Ok(SSCC96 {
         filter,
         partition,
         indicator,
         company,
         serial,
-    }))
+    })
+}
+
+fn parse_sscc(filter: u8, company: &str, serial: &str) -> Result<SSCC96> {
+    // Check the serial is ASCII before splitting off the extension digit
+    if company.len() + serial.len() != 17 || !serial.is_ascii() {
+        return Err(Gs1Error::Parse);
+    }
+    // The company prefix length is only given by the number of digits in the URI, including any
+    // leading zeros
+    let partition = PARTITIONS
+        .partition(company.len())
+        .map_err(|_| Gs1Error::Parse)?;
+
+    Ok(SSCC96 {
+        filter,
+        partition,
+        indicator: parse_decimal::<u8>(&serial[..1])?,
+        company: parse_decimal::<u64>(company)?,
+        serial: parse_decimal::<u64>(&serial[1..])?,
+    })
+}
+
+// GS1 EPC TDS Section 12.4
+pub(super) fn sscc96_from_tag_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [filter, company, serial] => Ok(Box::new(parse_sscc(
+            parse_filter(filter)?,
+            company,
+            serial,
+        )?)),
+        _ => Err(Gs1Error::Parse),
+    }
+}
+
+// GS1 EPC TDS Section 6.3.2
+pub(super) fn sscc_from_uri(fields: &[&str]) -> Result<Box<dyn EPC>> {
+    match fields {
+        [company, serial] => Ok(Box::new(parse_sscc(0, company, serial)?)),
+        _ => Err(Gs1Error::Parse),
+    }
 }
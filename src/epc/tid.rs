@@ -10,11 +10,13 @@
 //!
 //! # Reference
 //! GS1 EPC TDS Section 16
-use crate::error::{ParseError, Result};
+use crate::error::{Gs1Error, Result};
+use alloc::vec::Vec;
 use bitreader::BitReader;
 
 /// Tag Identification
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TID {
     /// Whether the Tag implements Extended Tag Identification
     pub xtid: bool,
@@ -36,7 +38,7 @@ pub struct TID {
 pub fn decode_tid(data: &[u8]) -> Result<TID> {
     let mut reader = BitReader::new(data);
     if reader.read_u8(8)? != 0xE2 {
-        return Err(Box::new(ParseError()));
+        return Err(Gs1Error::Parse);
     }
 
     Ok(TID {
@@ -48,8 +50,25 @@ pub fn decode_tid(data: &[u8]) -> Result<TID> {
     })
 }
 
+/// Encode the TID structure to bytes
+///
+/// This is the inverse of `decode_tid`. The MDID and TMID are truncated to their field widths of
+/// 9 and 12 bits respectively.
+///
+/// Reference: GS1 EPC TDS Section 16.2
+pub fn encode_tid(tid: &TID) -> Vec<u8> {
+    let value = 0xE2 << 24
+        | (tid.xtid as u32) << 23
+        | (tid.security as u32) << 22
+        | (tid.file as u32) << 21
+        | (tid.mdid as u32 & 0x1FF) << 12
+        | (tid.tmid as u32 & 0xFFF);
+    value.to_be_bytes().to_vec()
+}
+
 /// Extended Tag ID header
-#[derive(PartialEq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct XTIDHeader {
     /// Whether a further XTID header is present - always false
     pub extended_header: bool,
@@ -70,16 +89,26 @@ pub struct XTIDHeader {
 ///
 /// Reference: GS1 EPC TDS Section 16.2.1
 pub fn decode_xtid_header(data: &[u8]) -> Result<XTIDHeader> {
+    read_xtid_header(data, false)
+}
+
+/// Decode the XTID header from bytes, returning `Gs1Error::Parse` if any of the bits reserved
+/// for future use are set.
+///
+/// Reference: GS1 EPC TDS Section 16.2.1
+pub fn decode_xtid_header_strict(data: &[u8]) -> Result<XTIDHeader> {
+    read_xtid_header(data, true)
+}
+
+fn read_xtid_header(data: &[u8], strict: bool) -> Result<XTIDHeader> {
     let mut reader = BitReader::new(data);
 
     let extended_header = reader.read_bool()?;
     // Reserved for future use bits - should be zero but it seems like they frequently aren't.
-    let _rfu = reader.read_u16(9)?;
-    /*
-    if rfu != 0 {
-        println!("RFU: {:?}", rfu);
-        return Err(Box::new(ParseError()));
-    }*/
+    let rfu = reader.read_u16(9)?;
+    if strict && rfu != 0 {
+        return Err(Gs1Error::Parse);
+    }
     let user_memory_permalock = reader.read_bool()?;
     let blockwrite_blockerase = reader.read_bool()?;
     let optional_command_support = reader.read_bool()?;
@@ -98,114 +127,355 @@ pub fn decode_xtid_header(data: &[u8]) -> Result<XTIDHeader> {
     })
 }
 
-/// Look up a mask designer ID and return a string of the manufacturer name
+/// Decode the XTID serial number segment from bytes
+///
+/// The serial number directly follows the XTID header, and its length in bits is given by
+/// `XTIDHeader::serial_size`. The raw serial number bytes are returned.
+///
+/// Reference: GS1 EPC TDS Section 16.2.2
+pub fn decode_xtid_serial(data: &[u8], serial_size: u16) -> Result<Vec<u8>> {
+    let mut reader = BitReader::new(data);
+    let mut serial = Vec::with_capacity(serial_size as usize / 8);
+
+    for _i in 0..serial_size / 8 {
+        serial.push(reader.read_u8(8)?);
+    }
+
+    Ok(serial)
+}
+
+/// The decoded TID memory, up to and including the XTID serial number
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullTid {
+    /// Tag Identification
+    pub tid: TID,
+    /// Extended Tag ID header, if the tag implements XTID
+    pub xtid: Option<XTIDHeader>,
+    /// Raw tag serial number, if the XTID includes a serial number segment
+    pub serial: Option<Vec<u8>>,
+}
+
+/// The result of feeding data into a `TidDecoder`
+#[derive(PartialEq, Debug, Clone)]
+pub enum TidDecodeState {
+    /// More data is required, and this many words should be read from the tag
+    NeedMore(usize),
+    /// The TID memory has been fully decoded
+    Complete(FullTid),
+}
+
+/// Progressive decoder for TID memory.
+///
+/// Data read from the tag is fed into the decoder, which reports how many more words should be
+/// read until the TID has been fully decoded.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::tid::{TidDecodeState, TidDecoder};
+/// let mut decoder = TidDecoder::new();
+/// assert_eq!(decoder.feed(&[0xE2, 0x00]).unwrap(), TidDecodeState::NeedMore(1));
+/// match decoder.feed(&[0x11, 0x05]).unwrap() {
+///     TidDecodeState::Complete(tid) => assert_eq!(tid.tid.mdid, 1),
+///     TidDecodeState::NeedMore(_) => panic!("Decoding should be complete"),
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct TidDecoder {
+    data: Vec<u8>,
+}
+
+impl TidDecoder {
+    pub fn new() -> TidDecoder {
+        TidDecoder::default()
+    }
+
+    /// Add data read from the tag, which should directly follow any data previously added.
+    pub fn feed(&mut self, words: &[u8]) -> Result<TidDecodeState> {
+        self.data.extend_from_slice(words);
+
+        if self.data.len() < 4 {
+            return Ok(self.need_bytes(4));
+        }
+        let tid = decode_tid(&self.data[..4])?;
+        if !tid.xtid {
+            return Ok(TidDecodeState::Complete(FullTid {
+                tid,
+                xtid: None,
+                serial: None,
+            }));
+        }
+
+        if self.data.len() < 6 {
+            return Ok(self.need_bytes(6));
+        }
+        let xtid = decode_xtid_header(&self.data[4..6])?;
+        if xtid.serial_size == 0 {
+            return Ok(TidDecodeState::Complete(FullTid {
+                tid,
+                xtid: Some(xtid),
+                serial: None,
+            }));
+        }
+
+        let serial_end = 6 + xtid.serial_size as usize / 8;
+        if self.data.len() < serial_end {
+            return Ok(self.need_bytes(serial_end));
+        }
+        Ok(TidDecodeState::Complete(FullTid {
+            tid,
+            xtid: Some(xtid),
+            serial: Some(decode_xtid_serial(&self.data[6..], xtid.serial_size)?),
+        }))
+    }
+
+    fn need_bytes(&self, bytes: usize) -> TidDecodeState {
+        TidDecodeState::NeedMore((bytes - self.data.len()).div_ceil(2))
+    }
+}
+
+/// Stateful reader for TID memory, which reads each structure in turn.
 ///
-/// These mappings are from the [listing on the GS1
+/// Data is added to the reader as it's read from the tag, and each `read_` method consumes the
+/// structure at the current position. If there isn't enough data for a structure yet,
+/// `Gs1Error::TooShort` is returned with the total number of bytes required, and the position
+/// isn't advanced, so the read can be retried once more data has been added.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::tid::TidReader;
+/// let mut reader = TidReader::new(&[0xE2, 0x80, 0x11, 0x05]);
+/// let tid = reader.read_structure().unwrap();
+/// assert!(tid.xtid);
+/// assert!(reader.read_xtid_header().is_err());
+///
+/// reader.extend(&[0x00, 0x00]);
+/// assert_eq!(reader.read_xtid_header().unwrap().serial_size, 0);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TidReader {
+    data: Vec<u8>,
+    position: usize,
+    serial_size: Option<u16>,
+}
+
+impl TidReader {
+    pub fn new(data: &[u8]) -> TidReader {
+        TidReader {
+            data: data.to_vec(),
+            ..TidReader::default()
+        }
+    }
+
+    /// Add data read from the tag, which should directly follow any data previously added.
+    pub fn extend(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    /// Return the number of bytes which have been consumed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // Return the next `bytes` bytes without consuming them.
+    fn peek(&self, bytes: usize) -> Result<&[u8]> {
+        let end = self.position + bytes;
+        self.data.get(self.position..end).ok_or(Gs1Error::TooShort {
+            needed: end,
+            got: self.data.len(),
+        })
+    }
+
+    /// Read the TID structure (4 bytes).
+    pub fn read_structure(&mut self) -> Result<TID> {
+        let tid = decode_tid(self.peek(4)?)?;
+        self.position += 4;
+        Ok(tid)
+    }
+
+    /// Read the XTID header (2 bytes), which follows the TID structure.
+    pub fn read_xtid_header(&mut self) -> Result<XTIDHeader> {
+        let xtid = decode_xtid_header(self.peek(2)?)?;
+        self.position += 2;
+        self.serial_size = Some(xtid.serial_size);
+        Ok(xtid)
+    }
+
+    /// Read the XTID serial number, which follows the XTID header.
+    ///
+    /// The length of the serial number is given by the XTID header, so it must have been read
+    /// first, otherwise `Gs1Error::Parse` is returned.
+    pub fn read_serial(&mut self) -> Result<Vec<u8>> {
+        let serial_size = self.serial_size.ok_or(Gs1Error::Parse)?;
+        let bytes = serial_size as usize / 8;
+        let serial = decode_xtid_serial(self.peek(bytes)?, serial_size)?;
+        self.position += bytes;
+        Ok(serial)
+    }
+}
+
+/// Mask designer IDs and manufacturer names, from the [listing on the GS1
 /// website](https://www.gs1.org/epcglobal/standards/mdid).
+///
+/// This is the table used by `mdid_name` and `mdid_from_name`. Use `lookup_mdid` to look up an
+/// MDID in a newer table.
+// These are all binary because that's how they are on the website, for some ridiculous reason.
+#[allow(clippy::unreadable_literal)]
+pub static MDID_TABLE: &[(u16, &str)] = &[
+    (0b000000001, "Impinj"),
+    (0b000000010, "Texas Instruments"),
+    (0b000000011, "Alien Technology"),
+    (0b000000100, "Intelleflex"),
+    (0b000000101, "Atmel"),
+    (0b000000110, "NXP Semiconductors"),
+    (0b000000111, "ST Microelectronics"),
+    (0b000001000, "EP Microelectronics"),
+    (0b000001001, "Motorola (formerly Symbol Technologies)"),
+    (0b000001010, "Sentech Snd Bhd"),
+    (0b000001011, "EM Microelectronics"),
+    (0b000001100, "Renesas Technology Corp."),
+    (0b000001101, "Mstar"),
+    (0b000001110, "Tyco International"),
+    (0b000001111, "Quanray Electronics"),
+    (0b000010000, "Fujitsu"),
+    (0b000010001, "LSIS"),
+    (0b000010010, "CAEN RFID srl"),
+    (0b000010011, "Productivity Engineering GmbH"),
+    (0b000010100, "Federal Electric Corp."),
+    (0b000010101, "ON Semiconductor"),
+    (0b000010110, "Ramtron"),
+    (0b000010111, "Tego"),
+    (0b000011000, "Ceitec S.A."),
+    (0b000011001, "CPA Wernher von Braun"),
+    (0b000011010, "TransCore"),
+    (0b000011011, "Nationz"),
+    (0b000011100, "Invengo"),
+    (0b000011101, "Kiloway"),
+    (0b000011110, "Longjing Microelectronics Co. Ltd."),
+    (0b000011111, "Chipus Microelectronics"),
+    (0b000100000, "ORIDAO"),
+    (0b000100001, "Maintag"),
+    (0b000100010, "Yangzhou Daoyuan Microelectronics Co. Ltd"),
+    (0b000100011, "Gate Elektronik"),
+    (0b000100100, "RFMicron, Inc."),
+    (0b000100101, "RST-Invent LLC"),
+    (0b000100110, "Crystone Technology"),
+    (0b000100111, "Shanghai Fudan Microelectronics Group "),
+    (0b000101000, "Farsens"),
+    (0b000101001, "Giesecke & Devrient GmbH"),
+    (0b000101010, "AWID"),
+    (0b000101011, "Unitec Semicondutores S/A"),
+    (0b000101100, "Q-Free ASA"),
+    (0b000101101, "Valid S.A."),
+    (0b000101110, "Fraunhofer IPMS"),
+    (0b000101111, "ams AG"),
+    (0b000110000, "Angstrem JSC"),
+    (0b000110001, "Honeywell"),
+    (0b000110010, "Huada Semiconductor Co. Ltd (HDSC)"),
+    (0b000110011, "Lapis Semiconductor Co., Ltd."),
+    (0b000110100, "PJSC Mikron"),
+    (0b000110101, "Hangzhou Landa Microelectronics Co., Ltd."),
+    (
+        0b000110110,
+        "Nanjing NARI Micro-Electronic Technology Co., Ltd.",
+    ),
+    (0b000110111, "Southwest Integrated Circuit Design Co., Ltd."),
+    (0b000111000, "Silictec"),
+    (0b000111001, "Nation RFID"),
+    (0b000111010, "Asygn"),
+    (0b000111011, "Suzhou HCTech Technology Co., Ltd."),
+    (0b000111100, "AXEM Technology"),
+];
+
+/// Look up a mask designer ID in a table of MDIDs and manufacturer names
+///
+/// # Example
+/// ```
+/// # use gs1::epc::tid::{lookup_mdid, MDID_TABLE};
+/// let table = [(0x1, "Impinj"), (0x1FF, "Acme Tags")];
+/// assert_eq!(lookup_mdid(&table, 0x1FF), Some("Acme Tags"));
+/// assert_eq!(lookup_mdid(MDID_TABLE, 0x1FF), None);
+/// ```
+pub fn lookup_mdid<'a>(table: &[(u16, &'a str)], mdid: u16) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(id, _)| *id == mdid)
+        .map(|(_, name)| *name)
+}
+
+/// Look up a mask designer ID and return a string of the manufacturer name
+///
+/// These mappings are from `MDID_TABLE`.
 pub fn mdid_name(mdid: &u16) -> &str {
-    // These are all binary because that's how they are on the website, for some ridiculous reason.
-    match mdid {
-        #![allow(clippy::unreadable_literal)]
-        0b000000001 => "Impinj",
-        0b000000010 => "Texas Instruments",
-        0b000000011 => "Alien Technology",
-        0b000000100 => "Intelleflex",
-        0b000000101 => "Atmel",
-        0b000000110 => "NXP Semiconductors",
-        0b000000111 => "ST Microelectronics",
-        0b000001000 => "EP Microelectronics",
-        0b000001001 => "Motorola (formerly Symbol Technologies)",
-        0b000001010 => "Sentech Snd Bhd",
-        0b000001011 => "EM Microelectronics",
-        0b000001100 => "Renesas Technology Corp.",
-        0b000001101 => "Mstar",
-        0b000001110 => "Tyco International",
-        0b000001111 => "Quanray Electronics",
-        0b000010000 => "Fujitsu",
-        0b000010001 => "LSIS",
-        0b000010010 => "CAEN RFID srl",
-        0b000010011 => "Productivity Engineering GmbH",
-        0b000010100 => "Federal Electric Corp.",
-        0b000010101 => "ON Semiconductor",
-        0b000010110 => "Ramtron",
-        0b000010111 => "Tego",
-        0b000011000 => "Ceitec S.A.",
-        0b000011001 => "CPA Wernher von Braun",
-        0b000011010 => "TransCore",
-        0b000011011 => "Nationz",
-        0b000011100 => "Invengo",
-        0b000011101 => "Kiloway",
-        0b000011110 => "Longjing Microelectronics Co. Ltd.",
-        0b000011111 => "Chipus Microelectronics",
-        0b000100000 => "ORIDAO",
-        0b000100001 => "Maintag",
-        0b000100010 => "Yangzhou Daoyuan Microelectronics Co. Ltd",
-        0b000100011 => "Gate Elektronik",
-        0b000100100 => "RFMicron, Inc.",
-        0b000100101 => "RST-Invent LLC",
-        0b000100110 => "Crystone Technology",
-        0b000100111 => "Shanghai Fudan Microelectronics Group ",
-        0b000101000 => "Farsens",
-        0b000101001 => "Giesecke & Devrient GmbH",
-        0b000101010 => "AWID",
-        0b000101011 => "Unitec Semicondutores S/A",
-        0b000101100 => "Q-Free ASA",
-        0b000101101 => "Valid S.A.",
-        0b000101110 => "Fraunhofer IPMS",
-        0b000101111 => "ams AG",
-        0b000110000 => "Angstrem JSC",
-        0b000110001 => "Honeywell",
-        0b000110010 => "Huada Semiconductor Co. Ltd (HDSC)",
-        0b000110011 => "Lapis Semiconductor Co., Ltd.",
-        0b000110100 => "PJSC Mikron",
-        0b000110101 => "Hangzhou Landa Microelectronics Co., Ltd.",
-        0b000110110 => "Nanjing NARI Micro-Electronic Technology Co., Ltd.",
-        0b000110111 => "Southwest Integrated Circuit Design Co., Ltd.",
-        0b000111000 => "Silictec",
-        0b000111001 => "Nation RFID",
-        0b000111010 => "Asygn",
-        0b000111011 => "Suzhou HCTech Technology Co., Ltd.",
-        0b000111100 => "AXEM Technology",
-        _unknown => "Unknown",
-    }
+    lookup_mdid(MDID_TABLE, *mdid).unwrap_or("Unknown")
 }
 
-/// Look up the model name of a tag given the MDID and TMID.
+/// Look up a manufacturer name and return its mask designer ID
+///
+/// This is the inverse of `mdid_name`. Names are compared case-insensitively, ignoring leading
+/// and trailing whitespace.
+///
+/// # Example
+/// ```
+/// # use gs1::epc::tid::mdid_from_name;
+/// assert_eq!(mdid_from_name("impinj"), Some(1));
+/// ```
+pub fn mdid_from_name(name: &str) -> Option<u16> {
+    let name = name.trim();
+    MDID_TABLE
+        .iter()
+        .find(|(_, mdid_name)| mdid_name.trim().eq_ignore_ascii_case(name))
+        .map(|(id, _)| *id)
+}
+
+/// Tag model names, indexed by MDID and TMID.
 ///
 /// This data has been extracted from various datasheets - it's definitely not complete and it may
-/// not be correct.
+/// not be correct. Use `lookup_tmid` to look up a model in a newer table.
+pub static TMID_TABLE: &[(u16, u16, &str)] = &[
+    // Impinj
+    (0x1, 0x100, "Monza 4D"),
+    (0x1, 0x105, "Monza 4QT"),
+    (0x1, 0x10C, "Monza 4E"),
+    (0x1, 0x130, "Monza 5"),
+    (0x1, 0x160, "Monza R6"),
+    // Alien
+    (0x3, 0x412, "Higgs-3"),
+    (0x3, 0x414, "Higgs-4"),
+    // NXP
+    (0x6, 0x003, "UCODE G2XM"),
+    (0x6, 0x004, "UCODE G2XL"),
+    (0x6, 0x806, "UCODE G2iL"),
+    (0x6, 0x807, "UCODE G2iL+"),
+    (0x6, 0x80A, "UCODE G2iM"),
+    (0x6, 0x80D, "UCODE i2c"),
+    (0x6, 0x88D, "UCODE i2c"),
+    (0x6, 0x810, "UCODE 7"),
+    (0x6, 0x890, "UCODE 7"),
+    (0x6, 0x891, "UCODE 7m"),
+    (0x6, 0x894, "UCODE 8"),
+    (0x6, 0x906, "UCODE G2iL"),
+    (0x6, 0x907, "UCODE G2iL+"),
+    (0x6, 0x994, "UCODE 8m"),
+    (0x6, 0xB06, "UCODE G2iL"),
+    (0x6, 0xB07, "UCODE G2iL+"),
+    // RFMicron
+    (0x24, 0x401, "Magnus S2"),
+    (0x24, 0x402, "Magnus S2"),
+    (0x24, 0x403, "Magnus S2"),
+];
+
+/// Look up a tag model in a table of MDIDs, TMIDs, and model names
+pub fn lookup_tmid<'a>(table: &[(u16, u16, &'a str)], mdid: u16, tmid: u16) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(m, t, _)| *m == mdid && *t == tmid)
+        .map(|(_, _, name)| *name)
+}
+
+/// Look up the model name of a tag given the MDID and TMID.
+///
+/// These mappings are from `TMID_TABLE`.
 pub fn tmid_name(mdid: u16, tmid: u16) -> &'static str {
-    match (mdid, tmid) {
-        // Impinj
-        (0x1, 0x100) => "Monza 4D",
-        (0x1, 0x105) => "Monza 4QT",
-        (0x1, 0x10C) => "Monza 4E",
-        (0x1, 0x130) => "Monza 5",
-        (0x1, 0x160) => "Monza R6",
-        // Alien
-        (0x3, 0x412) => "Higgs-3",
-        (0x3, 0x414) => "Higgs-4",
-        // NXP
-        (0x6, 0x003) => "UCODE G2XM",
-        (0x6, 0x004) => "UCODE G2XL",
-        (0x6, 0x806) => "UCODE G2iL",
-        (0x6, 0x807) => "UCODE G2iL+",
-        (0x6, 0x80A) => "UCODE G2iM",
-        (0x6, 0x80D) => "UCODE i2c",
-        (0x6, 0x88D) => "UCODE i2c",
-        (0x6, 0x810) => "UCODE 7",
-        (0x6, 0x890) => "UCODE 7",
-        (0x6, 0x891) => "UCODE 7m",
-        (0x6, 0x894) => "UCODE 8",
-        (0x6, 0x906) => "UCODE G2iL",
-        (0x6, 0x907) => "UCODE G2iL+",
-        (0x6, 0x994) => "UCODE 8m",
-        (0x6, 0xB06) => "UCODE G2iL",
-        (0x6, 0xB07) => "UCODE G2iL+",
-        // RFMicron
-        (0x24, 0x401) => "Magnus S2",
-        (0x24, 0x402) => "Magnus S2",
-        (0x24, 0x403) => "Magnus S2",
-        _unknown => "Unknown",
-    }
+    lookup_tmid(TMID_TABLE, mdid, tmid).unwrap_or("Unknown")
 }
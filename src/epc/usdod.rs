@@ -0,0 +1,82 @@
+//! US Department of Defense Identifier
+//!
+//! This is a combination of a CAGE code or DoDAAC, which identifies the government-managed
+//! entity which applied the tag, and a serial number which allows an item to be uniquely
+//! identified. It's defined by the US Department of Defense rather than GS1.
+use crate::epc::{EPCValue, OwnedEPCValue, EPC};
+use crate::error::{Gs1Error, Result};
+use alloc::{boxed::Box, string::String};
+use bitreader::BitReader;
+use core::fmt;
+
+/// 96-bit US Department of Defense Identifier
+///
+/// This comprises a filter value, a CAGE code or DoDAAC, and a numeric serial number.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct USDoD96 {
+    /// Filter
+    pub filter: u8,
+    /// CAGE code (5 characters) or DoDAAC (6 characters)
+    pub cage_or_dodaac: String,
+    /// Serial number
+    pub serial: u64,
+}
+
+impl EPC for USDoD96 {
+    // GS1 EPC TDS section 6.3.17
+    fn write_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
+            "urn:epc:id:usdod:{}.{}",
+            self.cage_or_dodaac, self.serial
+        )
+    }
+
+    fn write_tag_uri(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(
+            w,
+            "urn:epc:tag:usdod-96:{}.{}.{}",
+            self.filter, self.cage_or_dodaac, self.serial
+        )
+    }
+
+    fn bit_length(&self) -> usize {
+        96
+    }
+
+    fn get_value(&self) -> EPCValue<'_> {
+        EPCValue::USDoD96(self)
+    }
+
+    fn get_owned_value(self: Box<Self>) -> OwnedEPCValue {
+        OwnedEPCValue::USDoD96(*self)
+    }
+}
+
+// GS1 EPC TDS Section 14.6.13
+pub(super) fn decode_usdod96(data: &[u8]) -> Result<USDoD96> {
+    let mut reader = BitReader::new(data);
+
+    let filter = reader.read_u8(4)?;
+
+    // The CAGE code or DoDAAC is six 8-bit ASCII characters. A five-character CAGE code is
+    // prefixed with a space.
+    let mut cage_or_dodaac = String::with_capacity(6);
+    for i in 0..6 {
+        let c = reader.read_u8(8)? as char;
+        match c {
+            ' ' if i == 0 => (),
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => cage_or_dodaac.push(c),
+            c => return Err(Gs1Error::InvalidCharacter(c)),
+        }
+    }
+
+    let serial = reader.read_u64(36)?;
+
+    Ok(USDoD96 {
+        filter,
+        cage_or_dodaac,
+        serial,
+    })
+}
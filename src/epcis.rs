@@ -0,0 +1,37 @@
+//! EPCIS integration
+//!
+//! EPCIS events identify the objects they refer to by a list of EPC pure identity URIs, such as
+//! the `epcList` of an `ObjectEvent`.
+use crate::epc::{decode_binary, EPCValue};
+use alloc::{string::String, vec::Vec};
+
+/// Decode the binary EPCs read from a set of tags into a list of pure identity URIs, for use as
+/// the EPC list of an EPCIS event.
+///
+/// Tags which can't be decoded are skipped, as are unprogrammed tags, which don't identify an
+/// object. Use `decode_binary` on each tag to find out why one was skipped. The URIs are in the
+/// same order as the tags, and duplicates aren't removed.
+///
+/// # Example
+/// ```
+/// # use gs1::epcis::epc_list;
+/// let sgtin = hex::decode("3074257BF7194E4000001A85").unwrap();
+/// let unprogrammed = [0u8; 12];
+/// let invalid = [0xFFu8; 12];
+/// assert_eq!(
+///     epc_list(&[&sgtin, &unprogrammed, &invalid]),
+///     vec!["urn:epc:id:sgtin:0614141.812345.6789"]
+/// );
+/// ```
+pub fn epc_list(tags: &[&[u8]]) -> Vec<String> {
+    tags.iter()
+        .filter_map(|tag| decode_binary(tag).ok())
+        .filter(|epc| {
+            !matches!(
+                epc.get_value(),
+                EPCValue::Unprogrammed(_) | EPCValue::Raw(_)
+            )
+        })
+        .map(|epc| epc.to_uri())
+        .collect()
+}
@@ -1,37 +1,91 @@
 //! Error types
-use std::error;
-use std::fmt;
+use crate::epc::EPCBinaryHeader;
+use bitreader::BitReaderError;
+use core::error;
+use core::fmt;
+use core::num::ParseIntError;
+use num_enum::TryFromPrimitiveError;
 
-pub type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+pub type Result<T> = core::result::Result<T, Gs1Error>;
 
-#[derive(Debug, Clone)]
-pub struct ParseError();
+/// Errors returned by this library.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gs1Error {
+    /// The input couldn't be parsed.
+    Parse,
+    /// The EPC header is valid, but decoding or encoding that scheme isn't implemented.
+    Unimplemented(EPCBinaryHeader),
+    /// The partition value of an EPC is outside the range defined by the standard.
+    InvalidPartition(u8),
+    /// A GS1 check digit doesn't match the rest of the code.
+    InvalidChecksum,
+    /// The input is shorter than the encoding requires. Lengths are in bytes.
+    TooShort { needed: usize, got: usize },
+    /// The length of an EPC given by the Gen2 PC word doesn't match the length of its encoding.
+    /// Lengths are in 16-bit words.
+    LengthMismatch { pc: usize, header: usize },
+    /// A field value is too large to be encoded. The field name is included.
+    OutOfRange(&'static str),
+    /// A character can't be encoded.
+    InvalidCharacter(char),
+    /// A field of a binary EPC has an invalid value. The offset of the start of the field is in
+    /// bits, from the start of the EPC header.
+    InvalidField { field: &'static str, offset: usize },
+}
 
-impl fmt::Display for ParseError {
+impl fmt::Display for Gs1Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "parse error")
+        match self {
+            Gs1Error::Parse => write!(f, "parse error"),
+            Gs1Error::Unimplemented(header) => write!(f, "unimplemented EPC scheme {:?}", header),
+            Gs1Error::InvalidPartition(partition) => {
+                write!(f, "invalid partition value {}", partition)
+            }
+            Gs1Error::InvalidChecksum => write!(f, "invalid check digit"),
+            Gs1Error::TooShort { needed, got } => {
+                write!(f, "input too short: needed {} bytes, got {}", needed, got)
+            }
+            Gs1Error::LengthMismatch { pc, header } => write!(
+                f,
+                "PC word gives EPC length {} words, but the header requires {}",
+                pc, header
+            ),
+            Gs1Error::OutOfRange(field) => write!(f, "{} is out of range", field),
+            Gs1Error::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Gs1Error::InvalidField { field, offset } => {
+                write!(f, "invalid {} at bit offset {}", field, offset)
+            }
+        }
     }
 }
 
-impl error::Error for ParseError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl error::Error for Gs1Error {}
+
+/// Running out of data maps to `TooShort`, with the lengths of the data given to the reader.
+impl From<BitReaderError> for Gs1Error {
+    fn from(err: BitReaderError) -> Self {
+        match err {
+            BitReaderError::NotEnoughData {
+                position,
+                length,
+                requested,
+            } => Gs1Error::TooShort {
+                needed: (position + requested).div_ceil(8) as usize,
+                got: length.div_ceil(8) as usize,
+            },
+            BitReaderError::TooManyBitsForType { .. } => Gs1Error::Parse,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct UnimplementedError();
-
-impl fmt::Display for UnimplementedError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "unimplemented")
+impl From<TryFromPrimitiveError<EPCBinaryHeader>> for Gs1Error {
+    fn from(_err: TryFromPrimitiveError<EPCBinaryHeader>) -> Self {
+        Gs1Error::Parse
     }
 }
 
-impl error::Error for UnimplementedError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl From<ParseIntError> for Gs1Error {
+    fn from(_err: ParseIntError) -> Self {
+        Gs1Error::Parse
     }
 }
@@ -0,0 +1,99 @@
+//! Global Location Number
+//!
+//! A GLN identifies a location, such as a warehouse or a shop, or a party, such as a company. It
+//! has the same structure as a GTIN-13: a company prefix, a location reference, and a check
+//! digit.
+use crate::checksum::{gs1_checksum, gs1_checksum_valid};
+use crate::company_prefix::company_prefix_length;
+use crate::error::{Gs1Error, Result};
+use crate::util::zero_pad;
+use crate::{ApplicationIdentifier, DEFAULT_COMPANY_DIGITS, GS1};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt;
+use core::str::FromStr;
+
+/// Global Location Number
+///
+/// GS1 General Specifications Section 3.7.9
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GLN {
+    /// Company identifier
+    pub company: u64,
+    /// Number of digits in the decimal representation of the company identifier
+    pub company_digits: usize,
+    /// Location reference
+    pub location: u64,
+}
+
+impl GLN {
+    /// Return the 13-digit representation of this GLN, including the check digit.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::gln::GLN;
+    /// let gln: GLN = "0614141000005".parse().unwrap();
+    /// assert_eq!(gln.to_gln13(), "0614141000005");
+    /// ```
+    pub fn to_gln13(&self) -> String {
+        // With a 12-digit company prefix, the location reference is empty
+        let location = match 12usize.saturating_sub(self.company_digits) {
+            0 => String::new(),
+            location_digits => zero_pad(self.location.to_string(), location_digits),
+        };
+        let body = format!(
+            "{}{}",
+            zero_pad(self.company.to_string(), self.company_digits),
+            location
+        );
+        format!("{}{}", body, gs1_checksum(&body))
+    }
+}
+
+/// Parse a 13-digit GLN, including its check digit.
+///
+/// The company prefix length is looked up with `company_prefix_length`, and set to
+/// `DEFAULT_COMPANY_DIGITS` if the prefix isn't known. Returns `Gs1Error::InvalidChecksum` if the
+/// check digit is incorrect.
+impl FromStr for GLN {
+    type Err = Gs1Error;
+
+    fn from_str(input: &str) -> Result<GLN> {
+        if input.len() != 13 || !input.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Gs1Error::Parse);
+        }
+        if !gs1_checksum_valid(input) {
+            return Err(Gs1Error::InvalidChecksum);
+        }
+
+        let company_digits = company_prefix_length(input).unwrap_or(DEFAULT_COMPANY_DIGITS);
+        Ok(GLN {
+            company: input[..company_digits].parse()?,
+            company_digits,
+            location: match &input[company_digits..12] {
+                "" => 0,
+                location => location.parse()?,
+            },
+        })
+    }
+}
+
+/// Display the GLN as a physical location element string, the same as `to_gs1`.
+impl fmt::Display for GLN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_gs1())
+    }
+}
+
+impl GS1 for GLN {
+    fn to_gs1(&self) -> String {
+        format!(
+            "({}) {}",
+            ApplicationIdentifier::LocationGLN as u16,
+            self.to_gln13()
+        )
+    }
+}
@@ -20,40 +20,56 @@
 //! The GS1 standards are [freely available](https://www.gs1.org/standards) and code in this
 //! library is cross-referenced to these wherever possible.
 //!
+//! # Features
+//!
+//! The `std` feature is enabled by default. Without it, the library is `no_std` and only
+//! requires `alloc`, so it can be used in embedded RFID readers. `from_digital_link` returns a
+//! `HashMap`, so it's only available with `std`; use `parse_digital_link` without it.
+//!
+//! The `chrono` feature adds the `date` module, which parses the dates in AIs such as the expiry
+//! date into `chrono::NaiveDate`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
 extern crate bitreader;
 extern crate num_enum;
-extern crate pad;
 extern crate percent_encoding;
 
-use crate::checksum::gs1_checksum;
+use crate::checksum::{gs1_checksum, gs1_checksum_valid};
+use crate::error::{Gs1Error, Result};
 use crate::util::zero_pad;
-use num_enum::IntoPrimitive;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+use core::str::FromStr;
 
+pub mod barcode;
 pub mod checksum;
+pub mod company_prefix;
+pub use company_prefix::company_prefix_length;
+use company_prefix::is_restricted_circulation;
+#[cfg(feature = "chrono")]
+pub mod date;
+#[cfg(all(feature = "chrono", feature = "std"))]
+pub use date::parse_date_ai;
+pub mod digital_link;
+#[cfg(feature = "std")]
+pub use digital_link::from_digital_link;
+pub use digital_link::parse_digital_link;
+pub mod element;
+pub use element::ElementStringBuilder;
 pub mod epc;
+pub mod epcis;
 pub mod error;
+pub mod gln;
 
+mod ai;
 mod util;
 
-// GS1 General Specifications, Figure 3.2-1
-#[repr(u16)]
-#[derive(Debug, IntoPrimitive)]
-#[allow(dead_code, clippy::upper_case_acronyms)]
-pub(crate) enum ApplicationIdentifier {
-    SSCC = 0,
-    GTIN = 1,
-    GTINContent = 2,
-    Batch = 10,
-    ProductionDate = 11,
-    DueDate = 12,
-    PackagingDate = 13,
-    BestBeforeDate = 15,
-    SellByDate = 16,
-    ExpirationDate = 17,
-    InternalProductVariant = 20,
-    SerialNumber = 21,
-}
+pub use crate::ai::{AiSpec, ApplicationIdentifier};
 
 /// A GS1 object which is capable of being represented as a GS1 element string.
 pub trait GS1 {
@@ -63,12 +79,194 @@ pub trait GS1 {
     fn to_gs1(&self) -> String;
 }
 
+/// The FNC1 separator, as represented in GS1-128 data strings (ASCII group separator)
+pub const FNC1: char = '\x1d';
+
+// AIs whose first two digits appear here have a predefined length, and so don't need to be
+// followed by FNC1 when concatenated. The length includes the AI itself.
+// GS1 General Specifications, Figure 5.10.1-2
+const PREDEFINED_LENGTH_AIS: [(&str, usize); 22] = [
+    ("00", 20),
+    ("01", 16),
+    ("02", 16),
+    ("03", 16),
+    ("04", 18),
+    ("11", 8),
+    ("12", 8),
+    ("13", 8),
+    ("14", 8),
+    ("15", 8),
+    ("16", 8),
+    ("17", 8),
+    ("18", 8),
+    ("19", 8),
+    ("20", 4),
+    ("31", 10),
+    ("32", 10),
+    ("33", 10),
+    ("34", 10),
+    ("35", 10),
+    ("36", 10),
+    ("41", 16),
+];
+
+fn predefined_length(ai: &str) -> Option<usize> {
+    PREDEFINED_LENGTH_AIS
+        .iter()
+        .find(|(prefix, _)| ai.starts_with(prefix))
+        .map(|(_, length)| *length)
+}
+
+fn has_predefined_length(ai: &str) -> bool {
+    predefined_length(ai).is_some()
+}
+
+// Split a human-readable element string, such as `(01) 80614141123458 (21) 6789`, into its
+// AIs and values.
+fn split_element_string(input: &str) -> Result<Vec<(String, String)>> {
+    let mut elements = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let (ai, remainder) = rest
+            .strip_prefix('(')
+            .and_then(|r| r.split_once(") "))
+            .ok_or(Gs1Error::Parse)?;
+        if ai.len() < 2 || !ai.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Gs1Error::Parse);
+        }
+        let (value, next) = match remainder.find(" (") {
+            Some(i) => (&remainder[..i], &remainder[i + 1..]),
+            None => (remainder, ""),
+        };
+        elements.push((ai.to_string(), value.to_string()));
+        rest = next;
+    }
+
+    Ok(elements)
+}
+
+// Split a GS1-128 data string, with variable-length elements separated by FNC1, into its AIs
+// and values.
+fn split_data_string(input: &str) -> Result<Vec<(String, String)>> {
+    let mut elements = Vec::new();
+    let mut rest = input.trim_start_matches(FNC1);
+
+    while !rest.is_empty() {
+        // As no AI is a prefix of another, the first matching length is the AI
+        let ai_length = (2..=4)
+            .find(|&n| {
+                rest.get(..n)
+                    .is_some_and(|ai| ai.parse::<ApplicationIdentifier>().is_ok())
+            })
+            .ok_or(Gs1Error::Parse)?;
+        let ai = &rest[..ai_length];
+
+        let value_end = match predefined_length(ai) {
+            Some(length) => length,
+            None => rest.find(FNC1).unwrap_or(rest.len()),
+        };
+        let value = rest.get(ai_length..value_end).ok_or(Gs1Error::Parse)?;
+        elements.push((ai.to_string(), value.to_string()));
+        // FNC1 is optional after a predefined-length element
+        rest = rest[value_end..].trim_start_matches(FNC1);
+    }
+
+    Ok(elements)
+}
+
+// Check that a value is valid for its AI, returning the AI.
+fn validate_element(ai: &str, value: &str) -> Result<ApplicationIdentifier> {
+    let ai: ApplicationIdentifier = ai.parse()?;
+    if !ai.spec().is_valid(value) {
+        return Err(Gs1Error::Parse);
+    }
+    Ok(ai)
+}
+
+/// Parse a GS1 element string into its AIs and values.
+///
+/// This accepts both the human-readable form produced by `GS1::to_gs1`, such as
+/// `(01) 80614141123458 (21) 6789`, and the GS1-128 data string form, where elements without a
+/// predefined length are separated by `FNC1`. A leading symbology identifier (such as `]C1`) is
+/// ignored.
+///
+/// Each value is checked against the format of its AI, as given by `ApplicationIdentifier::spec`.
+///
+/// # Example
+/// ```
+/// # use gs1::{parse_element_string, ApplicationIdentifier, FNC1};
+/// let input = format!("10ABC123{}0180614141123458", FNC1);
+/// assert_eq!(
+///     parse_element_string(&input).unwrap(),
+///     vec![
+///         (ApplicationIdentifier::Batch, "ABC123".to_string()),
+///         (ApplicationIdentifier::GTIN, "80614141123458".to_string()),
+///     ]
+/// );
+/// ```
+pub fn parse_element_string(input: &str) -> Result<Vec<(ApplicationIdentifier, String)>> {
+    let input = match input.get(..3) {
+        Some(prefix) if prefix.starts_with(']') => &input[3..],
+        _ => input,
+    };
+    let elements = if input.starts_with('(') {
+        split_element_string(input)?
+    } else {
+        split_data_string(input)?
+    };
+
+    elements
+        .into_iter()
+        .map(|(ai, value)| Ok((validate_element(&ai, &value)?, value)))
+        .collect()
+}
+
+/// Combine the element strings of several GS1 objects into a single GS1-128 data string.
+///
+/// Elements with a predefined length are placed first, and variable-length elements are
+/// separated with `FNC1`. An AI which appears in more than one object with the same value is only
+/// included once, but conflicting values for the same AI will return an error, as will values
+/// which aren't valid for their AI.
+///
+/// # Example
+/// ```
+/// # use gs1::{combine_gs1, GTIN};
+/// let gtin = GTIN {
+///     company: 614141,
+///     company_digits: 7,
+///     item: 12345,
+///     indicator: 8,
+/// };
+/// assert_eq!(combine_gs1(&[&gtin]).unwrap(), "0180614141123458");
+/// ```
+pub fn combine_gs1(objects: &[&dyn GS1]) -> Result<String> {
+    let mut elements: Vec<(ApplicationIdentifier, String)> = Vec::new();
+
+    for object in objects {
+        for (ai, value) in split_element_string(&object.to_gs1())? {
+            let ai = validate_element(&ai, &value)?;
+            match elements.iter().find(|(existing_ai, _)| *existing_ai == ai) {
+                Some((_, existing_value)) if *existing_value == value => {}
+                Some(_) => return Err(Gs1Error::Parse),
+                None => elements.push((ai, value)),
+            }
+        }
+    }
+
+    // Stable sort, so the original order is otherwise preserved
+    elements.sort_by_key(|(ai, _)| !has_predefined_length(&ai.to_digits()));
+
+    Ok(barcode::to_gs1_128(&elements))
+}
+
 /// Global Trade Item Number
 ///
 /// This is the most-used GS1 identifier, and is a superset of UPC, EAN, and ISBN codes.
 ///
 /// GS1 General Specifications Section 3.3.2
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GTIN {
     /// Company identifier
     pub company: u64,
@@ -80,19 +278,337 @@ pub struct GTIN {
     pub indicator: u8,
 }
 
-impl GS1 for GTIN {
-    fn to_gs1(&self) -> String {
+impl GTIN {
+    /// Create a GTIN from its fields, checking that they fit in a GTIN-14.
+    ///
+    /// The company prefix has `company_digits` digits, and the item reference takes up the
+    /// remaining `12 - company_digits` digits. Returns `Gs1Error::OutOfRange` if a field is too
+    /// large. The check digit is calculated when the GTIN is formatted.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::GTIN;
+    /// let gtin = GTIN::new(614141, 7, 12345, 8).unwrap();
+    /// assert_eq!(gtin.to_gtin14(), "80614141123458");
+    /// assert!(GTIN::new(614141, 7, 123456, 8).is_err());
+    /// ```
+    pub fn new(company: u64, company_digits: usize, item: u64, indicator: u8) -> Result<GTIN> {
+        if company_digits > 12 {
+            return Err(Gs1Error::OutOfRange("company_digits"));
+        }
+        if company >= 10u64.pow(company_digits as u32) {
+            return Err(Gs1Error::OutOfRange("company"));
+        }
+        if item >= 10u64.pow(12 - company_digits as u32) {
+            return Err(Gs1Error::OutOfRange("item"));
+        }
+        if indicator > 9 {
+            return Err(Gs1Error::OutOfRange("indicator"));
+        }
+        Ok(GTIN {
+            company,
+            company_digits,
+            item,
+            indicator,
+        })
+    }
+
+    /// Set the indicator digit, which identifies a packaging level in a GTIN-14.
+    ///
+    /// Returns `Gs1Error::OutOfRange` if the indicator isn't a single digit, leaving the GTIN
+    /// unchanged. An indicator of 0 makes this a GTIN-13 (or shorter) padded to 14 digits.
+    ///
+    /// A `GTIN` is always stored in its 14-digit form, and doesn't record whether it was read
+    /// from a UPC-A, EAN-13 or EAN-8 barcode. Any of these can be given an indicator to identify
+    /// a higher packaging level, so a non-zero indicator isn't rejected here. Callers which need
+    /// a GTIN-13 or shorter, such as when printing an EAN-13, must keep the indicator at 0.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::GTIN;
+    /// let mut gtin: GTIN = "0614141123452".parse().unwrap();
+    /// gtin.set_indicator(8).unwrap();
+    /// assert_eq!(gtin.to_gtin14(), "80614141123458");
+    /// assert!(gtin.set_indicator(10).is_err());
+    /// ```
+    pub fn set_indicator(&mut self, indicator: u8) -> Result<()> {
+        if indicator > 9 {
+            return Err(Gs1Error::OutOfRange("indicator"));
+        }
+        self.indicator = indicator;
+        Ok(())
+    }
+
+    /// Parse a GTIN-8, GTIN-12, GTIN-13 or GTIN-14, splitting it using a known company prefix
+    /// length.
+    ///
+    /// Parsing with `from_str` guesses the company prefix length from the GS1 prefix, which may
+    /// not give the right split. The indicator digit is taken from GTIN-14s, and is 0 otherwise.
+    ///
+    /// Returns `Gs1Error::Parse` if the input isn't 8, 12, 13 or 14 digits,
+    /// `Gs1Error::InvalidChecksum` if the check digit is incorrect, and
+    /// `Gs1Error::OutOfRange("company_digits")` if the company prefix length is over 12.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::GTIN;
+    /// let gtin = GTIN::parse_with_prefix("80614141123458", 9).unwrap();
+    /// assert_eq!(gtin.company, 61414112);
+    /// assert_eq!(gtin.item, 345);
+    /// assert_eq!(gtin.indicator, 8);
+    /// ```
+    pub fn parse_with_prefix(digits: &str, company_digits: usize) -> Result<GTIN> {
+        let gtin14 = check_gtin(digits)?;
+        GTIN::split(&gtin14, company_digits)
+    }
+
+    // Split a GTIN-14 into its fields. The check digit isn't verified.
+    fn split(gtin14: &str, company_digits: usize) -> Result<GTIN> {
+        if company_digits > 12 {
+            return Err(Gs1Error::OutOfRange("company_digits"));
+        }
+        let company_end = 1 + company_digits;
+        Ok(GTIN {
+            indicator: gtin14[..1].parse()?,
+            company: parse_digits(&gtin14[1..company_end])?,
+            company_digits,
+            item: parse_digits(&gtin14[company_end..13])?,
+        })
+    }
+
+    /// Append the check digit to the body of a GTIN-8, GTIN-12, GTIN-13 or GTIN-14, returning
+    /// the 14-digit GTIN.
+    ///
+    /// The body is the GTIN without its check digit, so it must have 7, 11, 12 or 13 digits.
+    /// Shorter bodies are zero-padded on the left, which doesn't change the check digit. Returns
+    /// `Gs1Error::Parse` if the body has any other length or contains anything other than decimal
+    /// digits.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::GTIN;
+    /// assert_eq!(GTIN::with_check_digit("8061414112345")?, "80614141123458");
+    /// assert_eq!(GTIN::with_check_digit("03600029145")?, "00036000291452");
+    /// # Ok::<(), gs1::error::Gs1Error>(())
+    /// ```
+    pub fn with_check_digit(body: &str) -> Result<String> {
+        if ![7, 11, 12, 13].contains(&body.len()) || !body.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Gs1Error::Parse);
+        }
+        let body = zero_pad(body.to_string(), 13);
+        Ok(format!("{}{}", body, gs1_checksum(&body)))
+    }
+
+    /// Return the canonical 14-digit representation of this GTIN, including the check digit.
+    ///
+    /// GTIN-8, GTIN-12 and GTIN-13 codes are zero-padded on the left, so this can be used as a
+    /// common key for GTINs from mixed sources.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::GTIN;
+    /// let gtin: GTIN = "036000291452".parse().unwrap();
+    /// assert_eq!(gtin.to_gtin14(), "00036000291452");
+    /// ```
+    pub fn to_gtin14(&self) -> String {
+        // With a 12-digit company prefix, the item reference is empty
+        let item = match 12usize.saturating_sub(self.company_digits) {
+            0 => String::new(),
+            item_digits => zero_pad(self.item.to_string(), item_digits),
+        };
         let element_string = format!(
             "{}{}{}",
             self.indicator,
             zero_pad(self.company.to_string(), self.company_digits),
-            zero_pad(self.item.to_string(), 12 - self.company_digits)
+            item
         );
+        format!("{}{}", element_string, gs1_checksum(&element_string))
+    }
+
+    /// Return the GTIN AI and the 14-digit GTIN, including the check digit.
+    ///
+    /// This is the form used by `ElementStringBuilder`, `to_gs1_128` and the other functions
+    /// which work on lists of AIs and values.
+    ///
+    /// # Example
+    /// ```
+    /// # use gs1::{ApplicationIdentifier, GTIN};
+    /// let gtin: GTIN = "80614141123458".parse().unwrap();
+    /// assert_eq!(
+    ///     gtin.as_ai(),
+    ///     (ApplicationIdentifier::GTIN, "80614141123458".to_string())
+    /// );
+    /// ```
+    pub fn as_ai(&self) -> (ApplicationIdentifier, String) {
+        (ApplicationIdentifier::GTIN, self.to_gtin14())
+    }
+
+    /// Parse a 12-digit UPC-A code, including its check digit.
+    ///
+    /// The UPC-A is zero-extended in the same way as any other GTIN-12, so the indicator is 0.
+    /// Returns `Gs1Error::InvalidChecksum` if the check digit is incorrect.
+    pub fn from_upc_a(input: &str) -> Result<GTIN> {
+        if input.len() != 12 {
+            return Err(Gs1Error::Parse);
+        }
+        input.parse()
+    }
+
+    /// Parse a 13-digit EAN-13 code, including its check digit.
+    ///
+    /// Returns `Gs1Error::InvalidChecksum` if the check digit is incorrect.
+    pub fn from_ean13(input: &str) -> Result<GTIN> {
+        if input.len() != 13 {
+            return Err(Gs1Error::Parse);
+        }
+        input.parse()
+    }
+
+    // The GTIN-13 form of this GTIN, if it has no indicator digit.
+    fn gtin13(&self) -> Option<String> {
+        match self.indicator {
+            0 => Some(self.to_gtin14()[1..].to_string()),
+            _ => None,
+        }
+    }
+
+    /// Return the ISBN-13 if this GTIN is in the 978 or 979 "Bookland" range.
+    ///
+    /// An ISBN-13 is identical to its GTIN-13, as the ISBN-13 check digit uses the same
+    /// algorithm as GS1. Use `isbn10` for the older 10-digit form.
+    pub fn isbn(&self) -> Option<String> {
+        self.gtin13()
+            .filter(|gtin| gtin.starts_with("978") || gtin.starts_with("979"))
+    }
+
+    /// Return the ISBN-10, with its modulus 11 check digit, if this GTIN is in the 978 range.
+    ///
+    /// ISBNs in the 979 range have no 10-digit form.
+    pub fn isbn10(&self) -> Option<String> {
+        let gtin = self.gtin13().filter(|gtin| gtin.starts_with("978"))?;
+        let body = &gtin[3..12];
+        Some(format!("{}{}", body, mod11_check_digit(body)))
+    }
+
+    /// Return the ISSN, formatted as `NNNN-NNNC`, if this GTIN is in the 977 range.
+    ///
+    /// The ISSN has its own modulus 11 check digit, which replaces the GS1 check digit. The
+    /// two-digit issue variant which follows the ISSN in the GTIN isn't included.
+    pub fn issn(&self) -> Option<String> {
+        let gtin = self.gtin13().filter(|gtin| gtin.starts_with("977"))?;
+        let body = &gtin[3..10];
+        Some(format!(
+            "{}-{}{}",
+            &body[..4],
+            &body[4..],
+            mod11_check_digit(body)
+        ))
+    }
+
+    /// Whether this GTIN is a globally unique identifier.
+    ///
+    /// Restricted Circulation Numbers are assigned for use within a company or region (such as
+    /// in-store codes for variable-measure items) and are not unique outside of that context.
+    /// They're identified by their prefix in the `company_prefix` table: GTIN-13s starting with
+    /// 2, GTIN-12s starting with 2 or 4, and GTIN-8s starting with 0 or 2.
+    pub fn is_globally_unique(&self) -> bool {
+        !is_restricted_circulation(&self.to_gtin14())
+    }
+}
+
+// The modulus 11 check digit used by ISBN-10 and ISSN, where the digits are weighted in
+// descending order, finishing with a weight of 2.
+fn mod11_check_digit(digits: &str) -> char {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .zip(2..)
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    match (11 - sum % 11) % 11 {
+        10 => 'X',
+        check => core::char::from_digit(check, 10).unwrap(),
+    }
+}
+
+/// The company prefix length assumed when parsing a GTIN whose prefix isn't in the
+/// `company_prefix_length` table.
+pub const DEFAULT_COMPANY_DIGITS: usize = 7;
+
+/// Parse a GTIN-8, GTIN-12 (UPC-A), GTIN-13 (EAN-13), or GTIN-14, including its check digit.
+///
+/// Shorter GTINs are zero-padded on the left to 14 digits, so their indicator digit is always 0.
+/// For a GTIN-14, the indicator is the first digit. The GTIN may be preceded by its `(01)`
+/// Application Identifier, so the output of `Display` can be parsed.
+///
+/// The length of the company prefix can't be determined from the GTIN alone, so `company_digits`
+/// is looked up with `company_prefix_length`, and set to `DEFAULT_COMPANY_DIGITS` if the prefix
+/// isn't known. This doesn't affect the GTIN's string representations.
+///
+/// # Example
+/// ```
+/// # use gs1::GTIN;
+/// let gtin: GTIN = "80614141123458".parse().unwrap();
+/// assert_eq!(gtin.indicator, 8);
+/// assert_eq!(gtin.company, 614141);
+/// assert_eq!(gtin.item, 12345);
+/// ```
+impl FromStr for GTIN {
+    type Err = Gs1Error;
+
+    fn from_str(input: &str) -> Result<GTIN> {
+        let input = input
+            .strip_prefix("(01)")
+            .map_or(input, |gtin| gtin.trim_start());
+        let gtin14 = check_gtin(input)?;
+        let company_digits = company_prefix_length(input).unwrap_or(DEFAULT_COMPANY_DIGITS);
+        GTIN::split(&gtin14, company_digits)
+    }
+}
+
+// Check the length and check digit of a GTIN-8, GTIN-12, GTIN-13 or GTIN-14, and return it
+// zero-padded to 14 digits.
+fn check_gtin(input: &str) -> Result<String> {
+    if ![8, 12, 13, 14].contains(&input.len()) || !input.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Gs1Error::Parse);
+    }
+    if !gs1_checksum_valid(input) {
+        return Err(Gs1Error::InvalidChecksum);
+    }
+    Ok(zero_pad(input.to_string(), 14))
+}
+
+// Parse a field which may be empty, such as the item reference with a 12-digit company prefix.
+fn parse_digits(digits: &str) -> Result<u64> {
+    match digits {
+        "" => Ok(0),
+        digits => Ok(digits.parse()?),
+    }
+}
+
+/// Display the GTIN as a GS1 element string, the same as `to_gs1`.
+///
+/// Use `to_gtin14` for the bare 14-digit string.
+///
+/// # Example
+/// ```
+/// # use gs1::GTIN;
+/// let gtin: GTIN = "80614141123458".parse().unwrap();
+/// assert_eq!(gtin.to_string(), "(01) 80614141123458");
+/// ```
+impl fmt::Display for GTIN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_gs1())
+    }
+}
+
+impl GS1 for GTIN {
+    fn to_gs1(&self) -> String {
         format!(
-            "({:0>2}) {}{}",
+            "({:0>2}) {}",
             ApplicationIdentifier::GTIN as u16,
-            element_string,
-            gs1_checksum(&element_string),
+            self.to_gtin14()
         )
     }
 }
@@ -1,45 +1,331 @@
-use crate::error::Result;
+use crate::error::{Gs1Error, Result};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use bitreader::BitReader;
-use pad::{Alignment, PadStr};
-use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
-use std::cmp;
+use core::cmp;
+use core::str::FromStr;
+use percent_encoding::{
+    percent_decode_str, utf8_percent_encode, AsciiSet, PercentEncode, CONTROLS,
+};
 
 // General utility functions for working with EPC
 
-// Read an EPC 7-bit ASCII string from the provided BitReader.
+// Accumulates bits, most significant first, for encoding EPCs. This is the counterpart to
+// BitReader.
+pub(crate) struct BitWriter {
+    data: Vec<u8>,
+    length: usize,
+}
+
+impl BitWriter {
+    pub(crate) fn new() -> BitWriter {
+        BitWriter {
+            data: Vec::new(),
+            length: 0,
+        }
+    }
+
+    // Write the low `bits` bits of `value`.
+    pub(crate) fn write_u64(&mut self, value: u64, bits: u8) {
+        for i in (0..bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    pub(crate) fn write_zeros(&mut self, bits: usize) {
+        for _i in 0..bits {
+            self.write_bit(false);
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        if self.length.is_multiple_of(8) {
+            self.data.push(0);
+        }
+        if bit {
+            let last = self.data.len() - 1;
+            self.data[last] |= 0x80 >> (self.length % 8);
+        }
+        self.length += 1;
+    }
+
+    // Return the written data, padded with zeros to a whole number of bytes.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+// Read an EPC 7-bit ASCII string from the provided BitReader, consuming at most `bits` bits.
+// The string ends at the first all-zero character, and any following bits in the field are
+// padding, which is ignored.
 // GS1 EPC TDS Section 14.4.2
 pub(crate) fn read_string(mut reader: BitReader, bits: u64) -> Result<String> {
-    let num_chars = cmp::min(reader.remaining(), bits) / 7;
-    let mut chars: Vec<char> = Vec::new();
+    let mut remaining = cmp::min(reader.remaining(), bits);
+    let mut result = String::new();
 
-    for _i in 0..num_chars {
+    while remaining >= 7 {
         let value = reader.read_u8(7)?;
-        if value != 0 {
-            chars.push(value as char);
+        remaining -= 7;
+        if value == 0 {
+            break;
         }
+        result.push(value as char);
     }
 
-    Ok(chars.iter().collect())
+    Ok(result)
 }
 
-pub(crate) fn uri_encode(input: String) -> String {
-    utf8_percent_encode(&input, NON_ALPHANUMERIC).to_string()
+// Write an EPC 7-bit ASCII string to the provided BitWriter, padding it with zeros to `bits`
+// bits. The caller must check that the string fits and only contains valid characters.
+// GS1 EPC TDS Section 14.3.2
+pub(crate) fn write_string(writer: &mut BitWriter, value: &str, bits: usize) {
+    for c in value.bytes() {
+        writer.write_u64(c as u64, 7);
+    }
+    writer.write_zeros(bits.saturating_sub(value.len() * 7));
+}
+
+// Read an EPC 6-bit string from the provided BitReader, consuming at most `bits` bits. The
+// string ends at the first all-zero terminator character, which is also consumed.
+// GS1 EPC TDS Section 14.4.3, Table G-1
+pub(crate) fn read_string_6bit(
+    reader: &mut BitReader,
+    bits: u64,
+    field: &'static str,
+) -> Result<String> {
+    let num_chars = cmp::min(reader.remaining(), bits) / 6;
+    let mut result = String::new();
+
+    for _i in 0..num_chars {
+        let position = reader.position();
+        let value = reader.read_u8(6)?;
+        let c = match value {
+            0 => break,
+            // A-Z are encoded in the low range, everything else as its ASCII value
+            0x01..=0x1A => (value | 0x40) as char,
+            0x23 | 0x2D | 0x2F | 0x30..=0x39 => value as char,
+            _ => return Err(invalid_field(field, position)),
+        };
+        result.push(c);
+    }
+
+    Ok(result)
+}
+
+// Read an EPC 6-bit string of at most `max_chars` characters, which must be followed by a
+// terminator character, as used for variable-length fields.
+pub(crate) fn read_terminated_string_6bit(
+    reader: &mut BitReader,
+    max_chars: u64,
+    field: &'static str,
+) -> Result<String> {
+    let start = reader.position();
+    let value = read_string_6bit(reader, (max_chars + 1) * 6, field)?;
+    // The terminator is consumed along with the string, so its absence means the field overran
+    if reader.position() - start == value.len() as u64 * 6 {
+        // If the data ran out before the field did, the read of the next character reports it
+        if (value.len() as u64) < max_chars {
+            reader.read_u8(6)?;
+        }
+        return Err(invalid_field(field, start));
+    }
+    Ok(value)
+}
+
+// Characters which must be escaped in the URI form of an EPC, GS1 EPC TDS Section 14.3.2. Other
+// characters allowed in EPC serials are left as they are.
+const EPC_URI_RESERVED: &AsciiSet = &CONTROLS
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?');
+
+// Percent-encode a string for an EPC URI. The result implements Display, so it can be written
+// without allocating.
+pub(crate) fn uri_encode(input: &str) -> PercentEncode<'_> {
+    utf8_percent_encode(input, EPC_URI_RESERVED)
+}
+
+pub(crate) fn uri_decode(input: &str) -> Result<String> {
+    Ok(percent_decode_str(input)
+        .decode_utf8()
+        .map_err(|_| Gs1Error::Parse)?
+        .to_string())
+}
+
+// Parse a field consisting only of decimal digits, as found in EPC URIs.
+pub(crate) fn parse_decimal<T: FromStr>(input: &str) -> Result<T> {
+    if input.is_empty() || !input.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Gs1Error::Parse);
+    }
+    input.parse::<T>().map_err(|_| Gs1Error::Parse)
+}
+
+// Parse the numeric serial of a 96-bit EPC from a URI. It must fit in `bits` bits, and leading
+// zeros aren't allowed as they can't be encoded.
+// GS1 EPC TDS Section 14.3.1
+pub(crate) fn parse_serial(input: &str, bits: u8) -> Result<u64> {
+    let value = parse_decimal::<u64>(input)?;
+    if value >= 1 << bits || (input != "0" && input.starts_with('0')) {
+        return Err(Gs1Error::Parse);
+    }
+    Ok(value)
+}
+
+// Parse the filter field of an EPC tag URI, which is always a 3-bit value.
+pub(crate) fn parse_filter(input: &str) -> Result<u8> {
+    let filter = parse_decimal::<u8>(input)?;
+    if filter > 7 {
+        return Err(Gs1Error::Parse);
+    }
+    Ok(filter)
+}
+
+// Whether a character is in the GS1 AI encodable character set 82, which is permitted in
+// alphanumeric serials and other free-text AI values.
+// GS1 EPC TDS Table A-1
+pub(crate) fn is_cset82(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(c)
+}
+
+// Some numeric EPC fields use zero to indicate that the corresponding part of the GS1 element
+// string is absent, such as the GDTI serial number and the SGLN extension. The GDTI-96 URIs
+// omit an absent serial, but the SGLN URIs include a zero extension.
+pub(crate) fn optional_field(value: u64) -> Option<u64> {
+    match value {
+        0 => None,
+        value => Some(value),
+    }
 }
 
 pub(crate) fn zero_pad(input: String, digits: usize) -> String {
-    input.pad(digits, '0', Alignment::Right, false)
+    format!("{:0>digits$}", input)
 }
 
+// Split the indicator (or extension) digit from the start of a partitioned item field. The field
+// holds exactly `item_digits` decimal digits once zero-padded, of which the first is the
+// indicator. Wider values come from corrupt data, or from a partition value which doesn't match
+// the encoding, so they're rejected rather than producing a nonsense identifier.
+//
+// This works on the integer directly rather than a padded string, as it's on the decode path
+// for every SGTIN and SSCC.
 pub(crate) fn extract_indicator(item: u64, item_digits: usize) -> Result<(u64, u8)> {
-    // The first character of the correctly-padded item string is the indicator digit or must be
-    // zero. I think.
-    // This is not terribly well spelled out in the GS1 EPC spec.
-    //
-    // TODO: error handling could be improved, but in practice most of these errors are probably
-    // unreachable.
-    let item_str = zero_pad(item.to_string(), item_digits);
-    let mut item_str_iterator = item_str.chars();
-    let indicator = item_str_iterator.next().unwrap().to_digit(10).unwrap() as u8;
-    let item = item_str_iterator.collect::<String>().parse::<u64>()?;
-    Ok((item, indicator))
+    if item_digits == 0 {
+        return Err(Gs1Error::Parse);
+    }
+    check_digits(item, item_digits)?;
+    let divisor = 10u64.pow(item_digits as u32 - 1);
+    Ok((item % divisor, (item / divisor) as u8))
+}
+
+// Return an error for an invalid field of a binary EPC, which starts at `position` in a reader
+// over the data following the 8-bit header.
+pub(crate) fn invalid_field(field: &'static str, position: u64) -> Gs1Error {
+    Gs1Error::InvalidField {
+        field,
+        offset: 8 + position as usize,
+    }
+}
+
+// Read a numeric field of `bits` bits, which must fit in the number of decimal digits its
+// partition allows.
+pub(crate) fn read_digits(
+    reader: &mut BitReader,
+    bits: u8,
+    digits: usize,
+    field: &'static str,
+) -> Result<u64> {
+    let position = reader.position();
+    let value = reader.read_u64(bits)?;
+    check_digits(value, digits).map_err(|_| invalid_field(field, position))?;
+    Ok(value)
+}
+
+// Check that a decoded value fits in the number of decimal digits its partition allows.
+pub(crate) fn check_digits(value: u64, digits: usize) -> Result<()> {
+    // Every u64 has fewer than 20 digits
+    if digits < 20 && value >= 10u64.pow(digits as u32) {
+        return Err(Gs1Error::Parse);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_extract_indicator() {
+    assert_eq!(extract_indicator(812345, 6).unwrap(), (12345, 8));
+    assert_eq!(extract_indicator(12345, 6).unwrap(), (12345, 0));
+    assert_eq!(extract_indicator(0, 1).unwrap(), (0, 0));
+    assert_eq!(extract_indicator(9_999_999, 7).unwrap(), (999_999, 9));
+    // Values wider than the partition allows
+    assert!(extract_indicator(15, 1).is_err());
+    assert!(extract_indicator(10_000_000, 7).is_err());
+}
+
+#[test]
+fn test_bit_writer() {
+    let mut writer = BitWriter::new();
+    writer.write_u64(0x30, 8);
+    writer.write_u64(0b101, 3);
+    writer.write_zeros(2);
+    writer.write_u64(0xFFF, 4);
+    assert_eq!(writer.into_bytes(), vec![0x30, 0xA7, 0x80]);
+}
+
+#[test]
+fn test_write_string() {
+    let mut writer = BitWriter::new();
+    write_string(&mut writer, "ab", 32);
+    let data = writer.into_bytes();
+    assert_eq!(data, vec![0xC3, 0x88, 0x00, 0x00]);
+    assert_eq!(read_string(BitReader::new(&data), 32).unwrap(), "ab");
+}
+
+#[test]
+fn test_read_string() {
+    // "ab", a terminator, and zero padding
+    let data = [0xC3, 0x88, 0x00, 0x00];
+    assert_eq!(read_string(BitReader::new(&data), 32).unwrap(), "ab");
+    assert_eq!(read_string(BitReader::new(&data), 14).unwrap(), "ab");
+
+    // Data after the terminator is padding
+    let data = [0xC3, 0x88, 0x03, 0x01];
+    assert_eq!(read_string(BitReader::new(&data), 32).unwrap(), "ab");
+}
+
+#[test]
+fn test_read_string_6bit() {
+    // "A1-/" followed by the terminator and a trailing field
+    let data = [0x07, 0x1B, 0x6F, 0x00, 0xF0];
+    let mut reader = BitReader::new(&data);
+    assert_eq!(read_string_6bit(&mut reader, 36, "test").unwrap(), "A1-/");
+    assert_eq!(reader.read_u8(6).unwrap(), 0x0F);
+
+    // Without a terminator, reading stops at the bit limit
+    let mut reader = BitReader::new(&data);
+    assert_eq!(read_string_6bit(&mut reader, 12, "test").unwrap(), "A1");
+
+    // 0x3F isn't in the 6-bit character set
+    let data = [0x07, 0xF0];
+    assert_eq!(
+        read_string_6bit(&mut BitReader::new(&data), 12, "test"),
+        Err(invalid_field("test", 6))
+    );
+}
+
+#[test]
+fn test_uri_encode() {
+    assert_eq!(uri_encode("32a.b-c_d").to_string(), "32a.b-c_d");
+    assert_eq!(uri_encode("32a/b").to_string(), "32a%2Fb");
+    assert_eq!(uri_encode("\"%&/<>?").to_string(), "%22%25%26%2F%3C%3E%3F");
+    assert_eq!(
+        uri_decode(&uri_encode("a.b/c%d").to_string()).unwrap(),
+        "a.b/c%d"
+    );
 }
@@ -0,0 +1,95 @@
+// Throw pseudo-random data at the decoders to check that they return errors rather than
+// panicking. This isn't a substitute for a real fuzzer, but catches the most obvious problems.
+use gs1::digital_link::decompress_gtin_serial;
+use gs1::epc::tid::decode_tid;
+use gs1::epc::{
+    decode_binary, decode_binary_stream, decode_binary_strict, decode_binary_with_pc, from_tag_uri,
+    from_uri, supported_schemes,
+};
+use gs1::{parse_element_string, GTIN};
+
+// A small xorshift generator, so the test is repeatable without extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+#[test]
+fn test_decode_random_data() {
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let schemes = supported_schemes();
+
+    for i in 0..5000 {
+        let len = (rng.next() % 40) as usize;
+        let mut data = rng.bytes(len);
+        // Most random headers are invalid, so use a supported scheme most of the time to reach
+        // the scheme decoders
+        if !data.is_empty() && i % 4 != 0 {
+            data[0] = schemes[rng.next() as usize % schemes.len()] as u8;
+        }
+
+        let _ = decode_binary(&data);
+        let _ = decode_binary_strict(&data);
+        let _ = decode_binary_with_pc(&data);
+        let _ = decode_binary_stream(&data);
+        let _ = decode_tid(&data);
+        if let Ok(epc) = decode_binary(&data) {
+            let _ = epc.to_uri();
+            let _ = epc.to_tag_uri();
+            let _ = epc.to_gs1_opt();
+            let _ = epc.to_binary();
+        }
+    }
+}
+
+#[test]
+fn test_parse_random_strings() {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let prefixes = [
+        "",
+        "urn:epc:id:sgtin:",
+        "urn:epc:id:sscc:",
+        "urn:epc:id:grai:",
+        "urn:epc:tag:sgtin-96:",
+        "urn:epc:tag:sgtin-198:",
+        "urn:epc:raw:",
+        "https://id.gs1.org/",
+        "]C1",
+        "(01)",
+    ];
+    // Mostly digits and separators, to get past the initial checks
+    let alphabet = b"0123456789...0123456789::%/()x#\x1dA\xc3\xa9";
+
+    for _i in 0..5000 {
+        let mut input = String::from(prefixes[rng.next() as usize % prefixes.len()]);
+        let len = (rng.next() % 40) as usize;
+        let bytes: Vec<u8> = (0..len)
+            .map(|_| alphabet[rng.next() as usize % alphabet.len()])
+            .collect();
+        input.push_str(&String::from_utf8_lossy(&bytes));
+
+        let _ = from_uri(&input);
+        let _ = from_tag_uri(&input);
+        let _ = parse_element_string(&input);
+        let _ = decompress_gtin_serial(&input);
+        let _ = input.parse::<GTIN>();
+    }
+}
+
+#[test]
+fn test_found_inputs() {
+    // Inputs which previously panicked or exhausted memory
+    assert!(from_uri("urn:epc:id:sgtin:0614141.\u{e9}2345.6789").is_err());
+    assert!(from_uri("urn:epc:id:sscc:0614141.\u{e9}23456789").is_err());
+    assert!(from_uri("urn:epc:raw:99999999999.1").is_err());
+}
@@ -1,6 +1,18 @@
-use gs1::epc::{decode_binary, EPCValue};
-use gs1::GS1;
-use hex;
+use std::collections::HashSet;
+
+use gs1::epc::gid::{encode_gid96, GID96};
+use gs1::epc::grai::{encode_grai96, GRAI96};
+use gs1::epc::sgtin::{encode_sgtin198, encode_sgtin96, ConformanceIssue, SGTIN198, SGTIN96};
+use gs1::epc::sscc::SSCC96;
+use gs1::epc::{
+    binary_to_uri, decode_binary, decode_binary_stream, decode_binary_strict, decode_binary_typed,
+    decode_binary_with_options, decode_binary_with_pc, decode_hex, decode_u128, filter_meaning,
+    from_raw_uri, from_tag_uri, from_uri, is_valid_epc, partition_for_company_digits, peek_header,
+    same_identity, DecodeOptions, EPCBinaryHeader, EPCValue, OwnedEPCValue, EPC,
+};
+use gs1::epcis::epc_list;
+use gs1::error::Gs1Error;
+use gs1::{GS1, GTIN};
 
 #[test]
 fn test_decode() {
@@ -47,27 +59,145 @@ fn test_decode() {
     };
 
     let data = [226, 0, 0, 25, 6, 12, 2, 9, 6, 144, 211, 194];
-    match decode_binary(&data) {
-        Err(_msg) => assert!(true),
-        _ => assert!(false),
-    };
+    assert!(decode_binary(&data).is_err());
 }
 
 #[test]
 fn test_bad_header() {
     // Header byte 0xE2 is permanently reserved and should result in a parse failure
     let data = [0xE2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-    match decode_binary(&data) {
-        Err(_e) => assert!(true),
-        _ => assert!(false),
+    assert!(decode_binary(&data).is_err());
+}
+
+#[test]
+fn test_short_input() {
+    assert!(decode_binary(&[]).is_err());
+    assert!(decode_binary(&[0x30]).is_err());
+    assert!(!is_valid_epc(&[]));
+
+    let options = DecodeOptions::new().with_unknown_as_raw(true);
+    assert!(decode_binary_with_options(&[], &options).is_err());
+}
+
+#[test]
+fn test_invalid_partition() {
+    // Partition value 7 is not defined for any scheme
+    for example in ["307C257BF7194E4000001A85", "317C257BF4499602D2000000"].iter() {
+        let err = decode_hex(example).err().unwrap();
+        assert_eq!(err, Gs1Error::InvalidPartition(7));
+    }
+}
+
+#[test]
+fn test_field_overflow() {
+    // SGTIN-96 with a 7-digit item reference (including indicator) in a 6-digit partition
+    assert_eq!(
+        decode_hex("3034257BF7D0900000001A85").err(),
+        Some(Gs1Error::InvalidField {
+            field: "item",
+            offset: 38
+        })
+    );
+    // SGTIN-96 with an 8-digit company prefix in a 7-digit partition
+    assert_eq!(
+        decode_hex("3036625A03194E4000001A85").err(),
+        Some(Gs1Error::InvalidField {
+            field: "company",
+            offset: 14
+        })
+    );
+    // SSCC-96 with an 11-digit serial reference in a 10-digit partition
+    assert_eq!(
+        decode_hex("3174257BF6540BE400000000").err(),
+        Some(Gs1Error::InvalidField {
+            field: "serial",
+            offset: 38
+        })
+    );
+    assert_eq!(
+        decode_hex("3174257BF6540BE400000000")
+            .err()
+            .unwrap()
+            .to_string(),
+        "invalid serial at bit offset 38"
+    );
+    // GIAI-96 with an 8-digit company prefix in a 7-digit partition
+    assert_eq!(
+        decode_hex("3416625A0000000000000000").err(),
+        Some(Gs1Error::InvalidField {
+            field: "company_prefix",
+            offset: 14
+        })
+    );
+}
+
+#[test]
+fn test_unknown_as_raw() {
+    // GSRN+ (0x2E) is a valid header which isn't implemented, so it's kept as Raw
+    let data = [0x2E, 0x74, 0x25, 0x7B, 0, 0, 0, 0, 0, 0, 0, 0x01];
+    let result = decode_binary(&data).unwrap();
+    assert_eq!(result.to_uri(), "urn:epc:raw:96.x2E74257B0000000000000001");
+    assert!(is_valid_epc(&data));
+
+    let val = match result.get_value() {
+        EPCValue::Raw(a) => a,
+        _ => {
+            panic!("Invalid type")
+        }
     };
+    assert_eq!(val.header, 0x2E);
+    assert_eq!(val.data, data[1..].to_vec());
+
+    // The raw URI can be parsed and converted back to binary
+    let parsed = from_uri(&result.to_uri()).unwrap();
+    assert_eq!(parsed.get_value(), result.get_value());
+    assert_eq!(parsed.to_binary().unwrap(), data);
+    let parsed = from_tag_uri(&result.to_tag_uri()).unwrap();
+    assert_eq!(parsed.get_value(), result.get_value());
+
+    // The data is kept even if it's shorter than the encoding
+    let result = decode_binary(&data[..4]).unwrap();
+    assert_eq!(result.to_uri(), "urn:epc:raw:32.x2E74257B");
+
+    // Header bytes which aren't defined by the standard are only kept with unknown_as_raw
+    let data = [0xE2, 0x74, 0x25, 0x7B];
+    assert!(decode_binary(&data).is_err());
+    let options = DecodeOptions::new().with_unknown_as_raw(true);
+    let result = decode_binary_with_options(&data, &options).unwrap();
+    assert_eq!(result.to_uri(), "urn:epc:raw:32.xE274257B");
+}
+
+#[test]
+fn test_from_raw_uri() {
+    let raw = from_raw_uri("urn:epc:raw:96.x2E74257B0000000000000001").unwrap();
+    assert_eq!(raw.header, 0x2E);
+    assert_eq!(raw.data.len(), 11);
+
+    // The decimal form of the same payload
+    let decimal = from_raw_uri("urn:epc:raw:96.14376722843366557388753600513").unwrap();
+    assert_eq!(decimal, raw);
+
+    // Lengths which aren't a whole number of bytes are padded on the right
+    let raw = from_raw_uri("urn:epc:raw:12.x2E7").unwrap();
+    assert_eq!((raw.header, raw.data), (0x2E, vec![0x70]));
+    let raw = from_raw_uri("urn:epc:raw:12.743").unwrap();
+    assert_eq!((raw.header, raw.data), (0x2E, vec![0x70]));
+
+    // The payload doesn't match the length
+    assert!(from_raw_uri("urn:epc:raw:96.x2E74").is_err());
+    assert!(from_raw_uri("urn:epc:raw:8.256").is_err());
+    assert!(from_raw_uri("urn:epc:raw:12.4096").is_err());
+    // The AFI form
+    assert!(from_raw_uri("urn:epc:raw:16.xA1.x2E74").is_err());
+    assert!(from_raw_uri("urn:epc:raw:0.x").is_err());
+    assert!(from_raw_uri("urn:epc:id:sgtin:0614141.812345.6789").is_err());
 }
 
 // Examples from GS1 EPC E.3
 #[test]
 fn test_examples() {
     // SGTIN-96
-    let data = decode_binary(&hex::decode("3074257BF7194E4000001A85").unwrap()).unwrap();
+    let data = decode_hex("3074257BF7194E4000001A85").unwrap();
     assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
     assert_eq!(
         data.to_tag_uri(),
@@ -83,10 +213,7 @@ fn test_examples() {
     assert_eq!(data.to_gs1(), "(01) 80614141123458 (21) 6789");
 
     // SGTIN-198
-    let data = decode_binary(
-        &hex::decode("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap(),
-    )
-    .unwrap();
+    let data = decode_hex("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
     assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.712345.32a%2Fb");
     assert_eq!(
         data.to_tag_uri(),
@@ -101,8 +228,40 @@ fn test_examples() {
     };
     assert_eq!(data.to_gs1(), "(01) 70614141123451 (21) 32a/b");
 
+    // A short serial is terminated by a null character and padded with zeros
+    let data = decode_hex("3674257BF6B7A660B10000000000000000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.712345.A1");
+
+    // Decoding stops at the terminator, and any characters after it are padding, so "AB\0CD"
+    // is read as "AB"
+    let data = decode_hex("3674257BF7194E60C2010E200000000000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.812345.AB");
+    let data = decode_hex("3674257BF6B7A660B10108000000000000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.712345.A1");
+    // ...unless the padding is required to be zero
+    for example in [
+        "3674257BF7194E60C2010E200000000000000000000000000000",
+        "3674257BF6B7A660B10108000000000000000000000000000000",
+    ]
+    .iter()
+    {
+        let data = hex::decode(example).unwrap();
+        assert_eq!(decode_binary_strict(&data).err(), Some(Gs1Error::Parse));
+    }
+    let data = decode_hex("3674257BF7194E60C20000000000000000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.812345.AB");
+
+    // Only the EPC reserved characters are escaped in URIs
+    let data = decode_hex("3674257BF6B7A660B15D896E3BF9000000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:0614141.712345.A1.b-c_d");
+    assert_eq!(from_uri(&data.to_uri()).unwrap().to_uri(), data.to_uri());
+    assert_eq!(
+        from_tag_uri(&data.to_tag_uri()).unwrap().to_tag_uri(),
+        data.to_tag_uri()
+    );
+
     // SSCC-96
-    let data = decode_binary(&hex::decode("3174257BF4499602D2000000").unwrap()).unwrap();
+    let data = decode_hex("3174257BF4499602D2000000").unwrap();
     assert_eq!(data.to_uri(), "urn:epc:id:sscc:0614141.1234567890");
     assert_eq!(
         data.to_tag_uri(),
@@ -117,16 +276,1391 @@ fn test_examples() {
     };
     assert_eq!(data.to_gs1(), "(00) 106141412345678908");
 
+    // SSCC-96 with a 6-digit company prefix, which has a 38-bit serial reference
+    let data = decode_hex("317BA1CC82DFDC1C35000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sscc:952114.12345678901");
+
     // GID-96
-    let data = decode_binary(&hex::decode("3500E86F8000A9E000000586").unwrap()).unwrap();
+    let data = decode_hex("3500E86F8000A9E000000586").unwrap();
     assert_eq!(data.to_uri(), "urn:epc:id:gid:952056.2718.1414");
     assert_eq!(data.to_tag_uri(), "urn:epc:tag:gid-96:952056.2718.1414");
 
     // GRAI-96
-    let data = decode_binary(&hex::decode("3376451FD40C0E400000162E").unwrap()).unwrap();
+    let data = decode_hex("3376451FD40C0E400000162E").unwrap();
     assert_eq!(data.to_uri(), "urn:epc:id:grai:9521141.12345.5678");
     assert_eq!(
         data.to_tag_uri(),
         "urn:epc:tag:grai-96:3.9521141.12345.5678"
     );
+
+    let data = match data.get_value() {
+        EPCValue::GRAI96(val) => val,
+        _ => {
+            panic!("Invalid type")
+        }
+    };
+    assert_eq!(data.to_gs1(), "(8003) 095211411234545678");
+
+    // The GRAI-96 decoder is public, and decodes the data following the header
+    let body = hex::decode("76451FD40C0E400000162E").unwrap();
+    let data = gs1::epc::grai::decode_grai96(&body).unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:grai:9521141.12345.5678");
+
+    // GRAI-170
+    let data = decode_hex("3776451FD40C0E59B2C2BF1000000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:grai:9521141.12345.32a%2Fb");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:grai-170:3.9521141.12345.32a%2Fb"
+    );
+
+    let data = match data.get_value() {
+        EPCValue::GRAI170(val) => val,
+        _ => {
+            panic!("Invalid type")
+        }
+    };
+    assert_eq!(data.serial, "32a/b");
+    assert_eq!(data.to_gs1(), "(8003) 0952114112345432a/b");
+
+    // SGLN-96
+    let data = decode_hex("3276451FD46072000000162E").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgln:9521141.12345.5678");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:sgln-96:3.9521141.12345.5678"
+    );
+
+    let data = match data.get_value() {
+        EPCValue::SGLN96(val) => val,
+        _ => {
+            panic!("Invalid type")
+        }
+    };
+    assert_eq!(data.company_prefix, 9521141);
+    assert_eq!(data.location, 12345);
+    assert_eq!(data.extension, 5678);
+    assert_eq!(data.to_gs1(), "(414) 9521141123454 (254) 5678");
+    assert_eq!(data.gln().to_gln13(), "9521141123454");
+
+    // GIAI-96
+    let data = decode_hex("3476451FD40000000000162E").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:giai:9521141.5678");
+    assert_eq!(data.to_tag_uri(), "urn:epc:tag:giai-96:3.9521141.5678");
+    assert_eq!(data.to_gs1_opt().unwrap(), "(8004) 95211415678");
+
+    // GDTI-96
+    let data = decode_hex("2C76451FD46072000000162E").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:gdti:9521141.12345.5678");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:gdti-96:3.9521141.12345.5678"
+    );
+
+    let data = match data.get_value() {
+        EPCValue::GDTI96(val) => val,
+        _ => {
+            panic!("Invalid type")
+        }
+    };
+    assert_eq!(data.to_gs1(), "(253) 95211411234545678");
+
+    // A serial number of 0 means that there isn't one, so it's omitted
+    let data = decode_hex("2C76451FD460720000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:gdti:9521141.12345");
+    assert_eq!(data.to_tag_uri(), "urn:epc:tag:gdti-96:3.9521141.12345");
+    assert_eq!(data.to_gs1_opt().unwrap(), "(253) 9521141123454");
+
+    // GSRN-96
+    let data = decode_hex("2D76451FD4499602D2000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:gsrn:9521141.1234567890");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:gsrn-96:3.9521141.1234567890"
+    );
+
+    let data = match data.get_value() {
+        EPCValue::GSRN96(val) => val,
+        _ => {
+            panic!("Invalid type")
+        }
+    };
+    assert_eq!(data.to_gs1(), "(8018) 952114112345678906");
+}
+
+#[test]
+fn test_from_tag_uri() {
+    let examples = [
+        "3074257BF7194E4000001A85",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+        "3174257BF4499602D2000000",
+        "3500E86F8000A9E000000586",
+        "3376451FD40C0E400000162E",
+    ];
+    for example in examples.iter() {
+        let decoded = decode_hex(example).unwrap();
+        let parsed = from_tag_uri(&decoded.to_tag_uri()).unwrap();
+        assert_eq!(parsed.get_value(), decoded.get_value());
+    }
+
+    // The SGTIN-198 serial is percent-decoded
+    let parsed = from_tag_uri("urn:epc:tag:sgtin-198:3.0614141.712345.32a%2Fb").unwrap();
+    match parsed.get_value() {
+        EPCValue::SGTIN198(val) => assert_eq!(val.serial, "32a/b"),
+        _ => panic!("Invalid type"),
+    };
+
+    // Unknown scheme
+    assert!(from_tag_uri("urn:epc:tag:foo-96:3.0614141.812345.6789").is_err());
+    // Not a tag URI
+    assert!(from_tag_uri("urn:epc:id:sgtin:0614141.812345.6789").is_err());
+    // Wrong number of fields
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345").is_err());
+    // Company and item digits don't add up to 13
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.81234.6789").is_err());
+    // The SGTIN-96 serial can't have leading zeros or exceed 38 bits
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.06789").is_err());
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.274877906944").is_err());
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.274877906943").is_ok());
+    assert!(from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.0").is_ok());
+    // Company and asset type digits don't add up to 12
+    assert!(from_tag_uri("urn:epc:tag:grai-96:3.9521141.123.5678").is_err());
+    assert!(from_tag_uri("urn:epc:tag:grai-96:3.9521141.12345.05678").is_err());
+    // Non-decimal field
+    assert!(from_tag_uri("urn:epc:tag:gid-96:952056.27a8.1414").is_err());
+}
+
+#[test]
+fn test_from_uri() {
+    let examples = [
+        "urn:epc:id:sgtin:0614141.812345.6789",
+        "urn:epc:id:sgtin:0614141.712345.32a%2Fb",
+        "urn:epc:id:sscc:0614141.1234567890",
+        "urn:epc:id:gid:952056.2718.1414",
+        "urn:epc:id:grai:9521141.12345.5678",
+    ];
+    for example in examples.iter() {
+        assert_eq!(from_uri(example).unwrap().to_uri(), *example);
+    }
+
+    // GRAI-96 company and asset type digits must add up to 12, and the serial can't have
+    // leading zeros or exceed 38 bits
+    for example in [
+        "urn:epc:id:grai:9521141.123.5678",
+        "urn:epc:id:grai:9521141.1234567.5678",
+        "urn:epc:id:grai:9521141.12345.05678",
+        "urn:epc:id:grai:9521141.12345.274877906944",
+    ]
+    .iter()
+    {
+        assert_eq!(from_uri(example).err(), Some(Gs1Error::Parse));
+    }
+
+    let parsed = from_uri("urn:epc:id:sgtin:0614141.812345.6789").unwrap();
+    let val = match parsed.get_value() {
+        EPCValue::SGTIN96(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    // The filter isn't included in the pure identity URI
+    assert_eq!(val.filter, 0);
+    assert_eq!(val.gtin.company, 614141);
+    assert_eq!(val.gtin.company_digits, 7);
+    assert_eq!(val.gtin.indicator, 8);
+    assert_eq!(val.gtin.item, 12345);
+    assert_eq!(val.serial, 6789);
+
+    // The SGTIN-198 serial is percent-decoded, and round-trips exactly
+    let parsed = from_uri("urn:epc:id:sgtin:0614141.712345.32a%2Fb").unwrap();
+    match parsed.get_value() {
+        EPCValue::SGTIN198(val) => {
+            assert_eq!(val.serial, "32a/b");
+            assert_eq!(
+                from_uri(&val.to_uri()).unwrap().get_value(),
+                parsed.get_value()
+            );
+        }
+        _ => panic!("Invalid type"),
+    };
+
+    // Serials which can't be encoded in SGTIN-96 are returned as SGTIN-198
+    let parsed = from_uri("urn:epc:id:sgtin:0614141.812345.06789").unwrap();
+    match parsed.get_value() {
+        EPCValue::SGTIN198(val) => assert_eq!(val.serial, "06789"),
+        _ => panic!("Invalid type"),
+    };
+
+    assert!(from_uri("urn:epc:id:foo:0614141.812345.6789").is_err());
+    assert!(from_uri("urn:epc:id:sscc:0614141.12345678x0").is_err());
+    assert!(from_uri("urn:epc:id:gid:952056.2718").is_err());
+}
+
+#[test]
+fn test_sgtin_conformance_check() {
+    // Filter value 3 is reserved
+    let data = decode_hex("3074257BF7194E4000001A85").unwrap();
+    let val = match data.get_value() {
+        EPCValue::SGTIN96(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    assert_eq!(
+        val.conformance_check(),
+        vec![ConformanceIssue::ReservedFilter(3)]
+    );
+
+    // Filter value 1, point of sale trade item
+    let data = decode_hex("3034257BF7194E4000001A85").unwrap();
+    let val = match data.get_value() {
+        EPCValue::SGTIN96(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    assert_eq!(val.conformance_check(), vec![]);
+
+    let data = decode_hex("3634257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    let val = match data.get_value() {
+        EPCValue::SGTIN198(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    assert_eq!(val.conformance_check(), vec![]);
+
+    let sgtin = SGTIN96 {
+        filter: 1,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 5,
+            item: 12345,
+            indicator: 8,
+        },
+        serial: 1 << 38,
+    };
+    assert_eq!(
+        sgtin.conformance_check(),
+        vec![
+            ConformanceIssue::InvalidCompanyDigits(5),
+            ConformanceIssue::NonCanonicalSerial
+        ]
+    );
+}
+
+#[test]
+fn test_is_valid_epc() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    assert!(is_valid_epc(&data));
+    assert!(!is_valid_epc(&data[..8]));
+    // Reserved header
+    assert!(!is_valid_epc(&[0xE2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+
+    // Schemes with string fields agree with a full decode
+    for example in [
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+        "3776451FD40C0E59B2C2BF1000000000000000000000",
+        "3E74257BF460730A7CC5ED1900000000000000000000",
+        "3B0E0CF5E76C9047759AD00373DC7602E7200000",
+        // An SGTIN-198 with an 8-digit company prefix in a 7-digit partition
+        "3636625A03194E40000000000000000000000000000000000000",
+        // GRAI-170 and GDTI-174 with partition 7
+        "377C0000000000000000000000000000000000000000",
+        "3E7C0000000000000000000000000000000000000000",
+        // An ADI-var with an empty serial
+        "3B057E316390C000",
+    ]
+    .iter()
+    {
+        let data = hex::decode(example).unwrap();
+        assert_eq!(
+            is_valid_epc(&data),
+            decode_binary(&data).is_ok(),
+            "{}",
+            example
+        );
+    }
+}
+
+#[test]
+fn test_get_owned_value() {
+    // The decoded value can be returned without keeping the decoded EPC alive
+    fn decode_sgtin(data: &[u8]) -> SGTIN96 {
+        match decode_binary(data).unwrap().get_owned_value() {
+            OwnedEPCValue::SGTIN96(val) => val,
+            _ => panic!("Invalid type"),
+        }
+    }
+
+    let sgtin = decode_sgtin(&hex::decode("3074257BF7194E4000001A85").unwrap());
+    assert_eq!(sgtin.gtin.company, 614141);
+    assert_eq!(sgtin.serial, 6789);
+
+    let value = decode_hex("3376451FD40C0E400000162E")
+        .unwrap()
+        .get_owned_value();
+    assert_eq!(value.to_uri(), "urn:epc:id:grai:9521141.12345.5678");
+    match value.get_value() {
+        EPCValue::GRAI96(val) => assert_eq!(val.serial, 5678),
+        _ => panic!("Invalid type"),
+    };
+}
+
+#[test]
+fn test_decode_binary_typed() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    match decode_binary_typed(&data).unwrap() {
+        OwnedEPCValue::SGTIN96(val) => {
+            assert_eq!(val.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789")
+        }
+        _ => panic!("Invalid type"),
+    };
+    assert_eq!(
+        decode_binary_typed(&data).unwrap(),
+        decode_binary(&data).unwrap().get_owned_value()
+    );
+
+    assert!(decode_binary_typed(&data[..8]).is_err());
+    assert!(decode_binary_typed(&[]).is_err());
+}
+
+#[test]
+fn test_try_from_binary() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    let sgtin = SGTIN96::try_from(data.as_slice()).unwrap();
+    assert_eq!(sgtin.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
+
+    // The header is for an SGTIN-96, not an SSCC-96
+    assert_eq!(
+        gs1::epc::sscc::SSCC96::try_from(data.as_slice()),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(SGTIN96::try_from(&[0u8; 12][..]), Err(Gs1Error::Parse));
+    assert_eq!(
+        SGTIN96::try_from(&data[..8]),
+        Err(Gs1Error::TooShort { needed: 12, got: 8 })
+    );
+    assert_eq!(SGTIN96::try_from(&[][..]), Err(Gs1Error::Parse));
+}
+
+#[test]
+fn test_display() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    let uri = "urn:epc:id:sgtin:0614141.812345.6789";
+
+    let epc = decode_binary(&data).unwrap();
+    assert_eq!(epc.to_string(), uri);
+    assert_eq!(format!("{}", epc.get_value()), uri);
+    assert_eq!(decode_binary_typed(&data).unwrap().to_string(), uri);
+}
+
+#[test]
+fn test_deduplicate() {
+    let reads = [
+        "3074257BF7194E4000001A85",
+        "3376451FD40C0E400000162E",
+        "3074257BF7194E4000001A85",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+        "3376451FD40C0E400000162E",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+    ];
+    let tags: HashSet<OwnedEPCValue> = reads
+        .iter()
+        .map(|read| decode_binary_typed(&hex::decode(read).unwrap()).unwrap())
+        .collect();
+    assert_eq!(tags.len(), 3);
+
+    let sgtin = match decode_binary_typed(&hex::decode(reads[0]).unwrap()).unwrap() {
+        OwnedEPCValue::SGTIN96(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    let mut gtins = HashSet::new();
+    gtins.insert(sgtin.gtin.clone());
+    gtins.insert(sgtin.clone().gtin);
+    assert_eq!(gtins.len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    let value = decode_binary_typed(&data).unwrap();
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(json["scheme"], "sgtin-96");
+    assert_eq!(json["gtin"]["company"], 614141);
+    assert_eq!(json["serial"], 6789);
+    assert_eq!(
+        serde_json::from_value::<OwnedEPCValue>(json.clone()).unwrap(),
+        value
+    );
+
+    // The borrowed and owned values serialize identically
+    let epc = decode_binary(&data).unwrap();
+    assert_eq!(serde_json::to_value(epc.get_value()).unwrap(), json);
+}
+
+#[test]
+fn test_sgtin96_from_gtin() {
+    let gtin = GTIN {
+        company: 614141,
+        company_digits: 7,
+        item: 12345,
+        indicator: 8,
+    };
+    let sgtin = SGTIN96::from_gtin(gtin.clone(), 6789, 3).unwrap();
+    assert_eq!(
+        sgtin.to_binary().unwrap(),
+        hex::decode("3074257BF7194E4000001A85").unwrap()
+    );
+
+    assert_eq!(
+        SGTIN96::from_gtin(gtin.clone(), (1 << 38) - 1, 0)
+            .unwrap()
+            .serial,
+        (1 << 38) - 1
+    );
+    assert_eq!(
+        SGTIN96::from_gtin(gtin.clone(), 1 << 38, 0),
+        Err(Gs1Error::OutOfRange("serial"))
+    );
+    assert_eq!(
+        SGTIN96::from_gtin(gtin, 6789, 8),
+        Err(Gs1Error::OutOfRange("filter"))
+    );
+}
+
+#[test]
+fn test_sgtin198_new() {
+    let gtin: GTIN = "80614141123458".parse().unwrap();
+    let sgtin = SGTIN198::new(1, gtin.clone(), "32a/b".to_string()).unwrap();
+    assert_eq!(sgtin.serial, "32a/b");
+    assert!(sgtin.conformance_check().is_empty());
+    assert!(sgtin.to_binary().is_ok());
+
+    assert_eq!(
+        SGTIN198::new(3, gtin.clone(), "12345678901234567890".to_string())
+            .unwrap()
+            .serial
+            .len(),
+        20
+    );
+    assert_eq!(
+        SGTIN198::new(3, gtin.clone(), "123456789012345678901".to_string()),
+        Err(Gs1Error::OutOfRange("serial"))
+    );
+    assert_eq!(
+        SGTIN198::new(3, gtin.clone(), "caf\u{e9}".to_string()),
+        Err(Gs1Error::InvalidCharacter('\u{e9}'))
+    );
+    assert_eq!(
+        SGTIN198::new(3, gtin.clone(), "a b".to_string()),
+        Err(Gs1Error::InvalidCharacter(' '))
+    );
+    assert_eq!(
+        SGTIN198::new(8, gtin, "6789".to_string()),
+        Err(Gs1Error::OutOfRange("filter"))
+    );
+}
+
+#[test]
+fn test_encode_gid96() {
+    let gid = GID96 {
+        manager: 123,
+        class: 456,
+        serial: 789,
+    };
+    assert_eq!(
+        encode_gid96(&gid).unwrap(),
+        hex::decode("35000007B0001C8000000315").unwrap()
+    );
+
+    let mut invalid = gid.clone();
+    invalid.manager = 1 << 28;
+    assert_eq!(encode_gid96(&invalid), Err(Gs1Error::OutOfRange("manager")));
+
+    let mut invalid = gid.clone();
+    invalid.class = 1 << 24;
+    assert_eq!(encode_gid96(&invalid), Err(Gs1Error::OutOfRange("class")));
+
+    let mut invalid = gid.clone();
+    invalid.serial = 1 << 36;
+    assert_eq!(encode_gid96(&invalid), Err(Gs1Error::OutOfRange("serial")));
+}
+
+#[test]
+fn test_encode_grai96() {
+    let grai = GRAI96 {
+        filter: 3,
+        partition: 5,
+        company_prefix: 9521141,
+        asset_type: 12345,
+        serial: 5678,
+    };
+    assert_eq!(
+        encode_grai96(&grai).unwrap(),
+        hex::decode("3376451FD40C0E400000162E").unwrap()
+    );
+
+    let mut invalid = grai.clone();
+    invalid.serial = 1 << 38;
+    assert_eq!(encode_grai96(&invalid), Err(Gs1Error::OutOfRange("serial")));
+
+    let mut invalid = grai.clone();
+    invalid.asset_type = 123456;
+    assert_eq!(
+        encode_grai96(&invalid),
+        Err(Gs1Error::OutOfRange("asset_type"))
+    );
+
+    let mut invalid = grai.clone();
+    invalid.partition = 7;
+    assert_eq!(encode_grai96(&invalid), Err(Gs1Error::InvalidPartition(7)));
+}
+
+#[test]
+fn test_grai_invalid_partition() {
+    let grai = GRAI96 {
+        filter: 3,
+        partition: 5,
+        company_prefix: 9521141,
+        asset_type: 12345,
+        serial: 5678,
+    };
+    assert_eq!(grai.to_grai().unwrap(), "095211411234545678");
+    assert_eq!(grai.to_gs1_opt().unwrap(), "(8003) 095211411234545678");
+
+    // Without a valid partition, the field widths and so the element string are unknown
+    let mut invalid = grai.clone();
+    invalid.partition = 7;
+    assert_eq!(invalid.to_grai(), Err(Gs1Error::InvalidPartition(7)));
+    assert_eq!(invalid.to_gs1_opt(), None);
+
+    let mut invalid = grai.clone();
+    invalid.asset_type = 123456;
+    assert_eq!(invalid.to_grai(), Err(Gs1Error::OutOfRange("asset_type")));
+}
+
+#[test]
+fn test_encode_sgtin96() {
+    let sgtin = SGTIN96 {
+        filter: 3,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        },
+        serial: 6789,
+    };
+    assert_eq!(
+        encode_sgtin96(&sgtin).unwrap(),
+        hex::decode("3074257BF7194E4000001A85").unwrap()
+    );
+
+    let mut invalid = sgtin.clone();
+    invalid.serial = 1 << 38;
+    assert_eq!(
+        encode_sgtin96(&invalid),
+        Err(Gs1Error::OutOfRange("serial"))
+    );
+}
+
+#[test]
+fn test_encode_sgtin198() {
+    // GS1 EPC TDS Appendix E.3
+    let data = hex::decode("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    let sgtin = match decode_binary_typed(&data).unwrap() {
+        OwnedEPCValue::SGTIN198(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    assert_eq!(encode_sgtin198(&sgtin).unwrap(), data[..25].to_vec());
+
+    let sgtin = SGTIN198 {
+        filter: 1,
+        gtin: GTIN {
+            company: 9521141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 0,
+        },
+        serial: "ABC-123.xyz".to_string(),
+    };
+    let encoded = encode_sgtin198(&sgtin).unwrap();
+    assert_eq!(encoded.len(), 25);
+    assert_eq!(
+        decode_binary_typed(&encoded).unwrap(),
+        OwnedEPCValue::SGTIN198(sgtin.clone())
+    );
+
+    let mut invalid = sgtin.clone();
+    invalid.serial = "A".repeat(21);
+    assert_eq!(
+        encode_sgtin198(&invalid),
+        Err(Gs1Error::OutOfRange("serial"))
+    );
+    invalid.serial = "A#1".to_string();
+    assert_eq!(
+        encode_sgtin198(&invalid),
+        Err(Gs1Error::InvalidCharacter('#'))
+    );
+    invalid.serial = "A1".to_string();
+    invalid.gtin.company_digits = 5;
+    assert_eq!(
+        encode_sgtin198(&invalid),
+        Err(Gs1Error::OutOfRange("company_digits"))
+    );
+}
+
+#[test]
+fn test_too_short() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    assert_eq!(
+        decode_binary_typed(&data[..11]),
+        Err(Gs1Error::TooShort {
+            needed: 12,
+            got: 11
+        })
+    );
+    assert!(!is_valid_epc(&data[..11]));
+
+    // Variable-length encodings run out of data part way through decoding
+    let data = hex::decode("3B0E0CF5E76C9047759AD00373DC7602E7200000").unwrap();
+    assert_eq!(
+        decode_binary_typed(&data[..10]),
+        Err(Gs1Error::TooShort {
+            needed: 11,
+            got: 10
+        })
+    );
+
+    let data = hex::decode("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    assert!(decode_binary_typed(&data[..25]).is_ok());
+    assert_eq!(
+        decode_binary_typed(&data[..20]),
+        Err(Gs1Error::TooShort {
+            needed: 25,
+            got: 20
+        })
+    );
+
+    assert_eq!(EPCBinaryHeader::SGTIN96.bit_length(), Some(96));
+    assert_eq!(EPCBinaryHeader::GRAI170.bit_length(), Some(170));
+    assert_eq!(EPCBinaryHeader::ADIVAR.bit_length(), None);
+}
+
+#[test]
+fn test_peek_header() {
+    let data = hex::decode("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    let header = peek_header(&data).unwrap();
+    assert_eq!(header, EPCBinaryHeader::SGTIN198);
+    assert_eq!(header.scheme_name(), "sgtin-198");
+    assert_eq!(
+        header.scheme_name(),
+        &decode_binary(&data).unwrap().to_tag_uri()[12..21]
+    );
+
+    // Headers which can't be decoded are still identified
+    assert_eq!(peek_header(&[0x3B]).unwrap().scheme_name(), "adi-var");
+    assert!(peek_header(&[]).is_err());
+    assert!(peek_header(&[0xFF]).is_err());
+}
+
+#[test]
+fn test_filter_meaning() {
+    let data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    let sgtin = match decode_binary_typed(&data).unwrap() {
+        OwnedEPCValue::SGTIN96(val) => val,
+        _ => panic!("Invalid type"),
+    };
+    // The TDS example uses a reserved filter value
+    assert_eq!(sgtin.filter, 3);
+    assert_eq!(
+        filter_meaning(peek_header(&data).unwrap(), sgtin.filter),
+        Some("Reserved")
+    );
+
+    assert_eq!(
+        filter_meaning(EPCBinaryHeader::SGTIN198, 1),
+        Some("Point of sale trade item")
+    );
+
+    assert_eq!(
+        filter_meaning(EPCBinaryHeader::SGLN96, 1),
+        Some("Physical location")
+    );
+    assert_eq!(
+        filter_meaning(EPCBinaryHeader::SGLN195, 1),
+        Some("Physical location")
+    );
+    assert_eq!(filter_meaning(EPCBinaryHeader::SGLN96, 2), Some("Reserved"));
+
+    assert_eq!(
+        filter_meaning(EPCBinaryHeader::SSCC96, 2),
+        Some("Full case for transport")
+    );
+    assert_eq!(filter_meaning(EPCBinaryHeader::SSCC96, 1), Some("Reserved"));
+    assert_eq!(
+        filter_meaning(EPCBinaryHeader::GRAI96, 0),
+        Some("All others")
+    );
+    assert_eq!(filter_meaning(EPCBinaryHeader::SGTIN96, 8), None);
+    assert_eq!(filter_meaning(EPCBinaryHeader::GID96, 0), None);
+}
+
+#[test]
+fn test_write_uri() {
+    let reads = [
+        "3074257BF7194E4000001A85",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+        "3174257BF4499602D2000000",
+        "3376451FD40C0E400000162E",
+    ];
+    let mut report = String::new();
+    let mut expected = Vec::new();
+    for read in reads.iter() {
+        let epc = decode_hex(read).unwrap();
+        epc.write_tag_uri(&mut report).unwrap();
+        report.push('\n');
+        expected.push(epc.to_tag_uri());
+    }
+    assert_eq!(report.lines().collect::<Vec<_>>(), expected);
+    assert_eq!(
+        report.lines().next().unwrap(),
+        "urn:epc:tag:sgtin-96:3.0614141.812345.6789"
+    );
+}
+
+#[test]
+fn test_decode_hex() {
+    let expected = "urn:epc:id:sgtin:0614141.812345.6789";
+    assert_eq!(
+        decode_hex("3074257BF7194E4000001A85").unwrap().to_uri(),
+        expected
+    );
+    assert_eq!(
+        decode_hex("3074257bf7194e4000001a85").unwrap().to_uri(),
+        expected
+    );
+    assert_eq!(
+        decode_hex(" 0x3074 257B F719 4E40 0000 1A85\n")
+            .unwrap()
+            .to_uri(),
+        expected
+    );
+
+    assert_eq!(
+        decode_hex("3074257BF7194E4000001A8").err(),
+        Some(Gs1Error::Parse)
+    );
+    assert_eq!(
+        decode_hex("3074257BF7194E4000001AZZ").err(),
+        Some(Gs1Error::Parse)
+    );
+    assert_eq!(decode_hex("").err(), Some(Gs1Error::Parse));
+}
+
+#[test]
+fn test_usdod96() {
+    // A five-character CAGE code, padded with a leading space
+    let data = decode_hex("2F320434147455900000162E").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:usdod:CAGEY.5678");
+    assert_eq!(data.to_tag_uri(), "urn:epc:tag:usdod-96:3.CAGEY.5678");
+    match data.get_value() {
+        EPCValue::USDoD96(val) => {
+            assert_eq!(val.filter, 3);
+            assert_eq!(val.cage_or_dodaac, "CAGEY");
+            assert_eq!(val.serial, 5678);
+        }
+        _ => panic!("Invalid type"),
+    }
+
+    // A six-character DoDAAC
+    let data = decode_hex("2F057313233343500000002A").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:usdod:W12345.42");
+    assert_eq!(data.to_tag_uri(), "urn:epc:tag:usdod-96:0.W12345.42");
+
+    // Lowercase characters and embedded spaces aren't permitted
+    assert_eq!(
+        decode_hex("2F320434147457900000162E").err(),
+        Some(Gs1Error::InvalidCharacter('y'))
+    );
+    assert_eq!(
+        decode_hex("2F343412047455900000162E").err(),
+        Some(Gs1Error::InvalidCharacter(' '))
+    );
+}
+
+#[test]
+fn test_adivar() {
+    // GS1 EPC TDS Appendix E, with a five-character CAGE code padded with a leading space
+    let data = decode_hex("3B0E0CF5E76C9047759AD00373DC7602E7200000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:adi:35962.PQ7VZ4.M37GXB92");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:adi-var:3.35962.PQ7VZ4.M37GXB92"
+    );
+    assert_eq!(data.bit_length(), 146);
+    match data.get_value() {
+        EPCValue::ADIVar(val) => {
+            assert_eq!(val.filter, 3);
+            assert_eq!(val.cage_or_dodaac, "35962");
+            assert_eq!(val.part_number, "PQ7VZ4");
+            assert_eq!(val.serial, "M37GXB92");
+        }
+        _ => panic!("Invalid type"),
+    }
+
+    // Reserved characters are escaped in the URI
+    let data = decode_hex("3B020C93C79D31CB3D35BC1C408D33ADC400").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:adi:2S194.12345%2FA1.%23SN-1");
+
+    // A six-character DoDAAC with no part number
+    let data = decode_hex("3B057E316390C00420C0").unwrap();
+    assert_eq!(data.to_tag_uri(), "urn:epc:tag:adi-var:1.W81X9C..ABC");
+
+    // '#' is only permitted at the start of the serial
+    assert_eq!(
+        decode_hex("3B017E316390D0C400630800").err(),
+        Some(Gs1Error::InvalidCharacter('#'))
+    );
+    // Only letters and digits are permitted in the CAGE code
+    assert_eq!(
+        decode_hex("3B017E31B790D0C400420000").err(),
+        Some(Gs1Error::InvalidCharacter('-'))
+    );
+    // 0x3F isn't in the 6-bit character set
+    assert_eq!(
+        decode_hex("3B057E316390FF000000").err(),
+        Some(Gs1Error::InvalidField {
+            field: "part_number",
+            offset: 50
+        })
+    );
+    // The serial may not be empty
+    assert_eq!(
+        decode_hex("3B057E316390C000").err(),
+        Some(Gs1Error::InvalidField {
+            field: "serial",
+            offset: 56
+        })
+    );
+    // The data ends before the serial's terminator
+    assert_eq!(
+        decode_hex("3B0E0CF5E76C9047759AD00373DC7602E7").err(),
+        Some(Gs1Error::TooShort {
+            needed: 18,
+            got: 17
+        })
+    );
+}
+
+#[test]
+fn test_sgcn96() {
+    // GS1 EPC TDS Appendix E
+    let data = decode_hex("3F74F4E4E612640000019907").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgcn:4012345.67890.04711");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:sgcn-96:3.4012345.67890.04711"
+    );
+    match data.get_value() {
+        EPCValue::SGCN96(val) => {
+            assert_eq!(val.company_prefix, 4012345);
+            assert_eq!(val.coupon_reference, 67890);
+            assert_eq!(val.serial, "04711");
+            assert_eq!(val.to_gs1(), "(255) 401234567890104711");
+        }
+        _ => panic!("Invalid type"),
+    }
+}
+
+#[test]
+fn test_cpi96() {
+    let data = decode_hex("3C74257BF406329C80001A85").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:cpi:0614141.812345.6789");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:cpi-96:3.0614141.812345.6789"
+    );
+    match data.get_value() {
+        EPCValue::CPI96(val) => {
+            assert_eq!(val.partition, 5);
+            assert_eq!(val.company_prefix, 614141);
+            assert_eq!(val.part_reference, 812345);
+            assert_eq!(val.serial, 6789);
+        }
+        _ => panic!("Invalid type"),
+    }
+}
+
+#[test]
+fn test_cpivar() {
+    // GS1 EPC TDS Appendix E
+    let data = decode_hex("3D74257BF75411DEF6B4CC00000003039000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:cpi:0614141.5PQ7%2FZ43.12345");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:cpi-var:3.0614141.5PQ7%2FZ43.12345"
+    );
+    assert_eq!(data.bit_length(), 132);
+    match data.get_value() {
+        EPCValue::CPIVar(val) => {
+            assert_eq!(val.partition, 5);
+            assert_eq!(val.company_prefix, 614141);
+            assert_eq!(val.part_reference, "5PQ7/Z43");
+            assert_eq!(val.serial, 12345);
+        }
+        _ => panic!("Invalid type"),
+    }
+
+    let data = decode_hex("3D1BA1CC810ADC728D800000000001C0").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:cpi:952114.AB-12%23X.7");
+
+    // The serial has more than 12 digits
+    assert_eq!(
+        decode_hex("3D1BA1CC810B1C80E8D4A5100000").err(),
+        Some(Gs1Error::InvalidField {
+            field: "serial",
+            offset: 64
+        })
+    );
+}
+
+#[test]
+fn test_itip110() {
+    let data = decode_hex("4014F4E4E40C0E40820000000F6C").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:itip:4012345.012345.01.02.987");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:itip-110:0.4012345.012345.01.02.987"
+    );
+    match data.get_value() {
+        EPCValue::ITIP110(val) => {
+            assert_eq!(val.gtin.to_gtin14(), "04012345123456");
+            assert_eq!(val.piece, 1);
+            assert_eq!(val.total, 2);
+            assert_eq!(val.serial, 987);
+            assert_eq!(val.to_gs1(), "(8006) 040123451234560102 (21) 987");
+        }
+        _ => panic!("Invalid type"),
+    }
+}
+
+#[test]
+fn test_gdti174() {
+    let data = decode_hex("3E74257BF460730A7CC5ED1900000000000000000000").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:gdti:0614141.12345.BOL%2F42");
+    assert_eq!(
+        data.to_tag_uri(),
+        "urn:epc:tag:gdti-174:3.0614141.12345.BOL%2F42"
+    );
+    match data.get_value() {
+        EPCValue::GDTI174(val) => {
+            assert_eq!(val.document_type, 12345);
+            assert_eq!(val.serial, "BOL/42");
+            assert_eq!(val.to_gs1(), "(253) 0614141123452BOL/42");
+        }
+        _ => panic!("Invalid type"),
+    }
+}
+
+#[test]
+fn test_decode_binary_stream() {
+    let data = hex::decode(concat!(
+        "3074257BF7194E4000001A85",
+        "3500E86F8000A9E000000586",
+        "3074257BF7194E4000001A86",
+    ))
+    .unwrap();
+    let uris: Vec<String> = decode_binary_stream(&data)
+        .into_iter()
+        .map(|epc| epc.unwrap().to_uri())
+        .collect();
+    assert_eq!(
+        uris,
+        [
+            "urn:epc:id:sgtin:0614141.812345.6789",
+            "urn:epc:id:gid:952056.2718.1414",
+            "urn:epc:id:sgtin:0614141.812345.6790",
+        ]
+    );
+    assert!(decode_binary_stream(&[]).is_empty());
+
+    // A truncated final tag
+    let epcs = decode_binary_stream(&data[..30]);
+    assert_eq!(epcs.len(), 3);
+    assert_eq!(
+        epcs[2].as_ref().err(),
+        Some(&Gs1Error::TooShort { needed: 12, got: 6 })
+    );
+
+    // Decoding stops at an encoding which isn't 96 bits long
+    let mut data = hex::decode("3074257BF7194E4000001A85").unwrap();
+    data.extend(hex::decode("3674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap());
+    let epcs = decode_binary_stream(&data);
+    assert_eq!(epcs.len(), 2);
+    assert_eq!(
+        epcs[1].as_ref().err(),
+        Some(&Gs1Error::Unimplemented(EPCBinaryHeader::SGTIN198))
+    );
+}
+
+#[test]
+fn test_sgtin_partition_extremes() {
+    // Partition 0: a 12-digit company prefix, leaving only the indicator digit
+    let data = decode_hex("3020393243F1660000001A85").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:061414112345.8.6789");
+    match data.get_value() {
+        EPCValue::SGTIN96(val) => {
+            assert_eq!(val.gtin.company_digits, 12);
+            assert_eq!(val.gtin.indicator, 8);
+            assert_eq!(val.gtin.item, 0);
+            assert_eq!(val.gtin.to_gtin14(), "80614141123458");
+        }
+        _ => panic!("Invalid type"),
+    }
+    let parsed = from_uri(&data.to_uri()).unwrap();
+    assert_eq!(parsed.to_uri(), data.to_uri());
+
+    // Partition 6: a 6-digit company prefix and a 6-digit item reference
+    let data = decode_hex("30383BF99EFD100000001A85").unwrap();
+    assert_eq!(data.to_uri(), "urn:epc:id:sgtin:061414.8123456.6789");
+    match data.get_value() {
+        EPCValue::SGTIN96(val) => {
+            assert_eq!(val.gtin.company_digits, 6);
+            assert_eq!(val.gtin.indicator, 8);
+            assert_eq!(val.gtin.item, 123456);
+            assert_eq!(val.gtin.to_gtin14(), "80614141234567");
+        }
+        _ => panic!("Invalid type"),
+    }
+    let parsed = from_uri(&data.to_uri()).unwrap();
+    assert_eq!(parsed.to_uri(), data.to_uri());
+
+    // A company prefix which is too long for any partition doesn't panic
+    let sgtin = SGTIN96 {
+        filter: 1,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 13,
+            item: 0,
+            indicator: 8,
+        },
+        serial: 6789,
+    };
+    assert_eq!(sgtin.to_uri(), "urn:epc:id:sgtin:0000000614141.8.6789");
+}
+
+#[test]
+fn test_bit_length() {
+    let examples = [
+        ("3074257BF7194E4000001A85", 96),
+        ("3674257BF6B7A659B2C2BF100000000000000000000000000000", 198),
+        ("3174257BF4499602D2000000", 96),
+        ("3500E86F8000A9E000000586", 96),
+        ("3376451FD40C0E400000162E", 96),
+        ("3776451FD40C0E59B2C2BF1000000000000000000000", 170),
+        ("3E74257BF460730A7CC5ED1900000000000000000000", 174),
+        ("4014F4E4E40C0E40820000000F6C", 110),
+        ("2F320434147455900000162E", 96),
+        ("3F74F4E4E612640000019907", 96),
+        ("3C74257BF406329C80001A85", 96),
+    ];
+    for (hex, bits) in examples.iter() {
+        let epc = decode_hex(hex).unwrap();
+        assert_eq!(epc.bit_length(), *bits, "{}", hex);
+        // The hex examples are padded to whole 16-bit words
+        assert_eq!(epc.word_length(), hex.len() / 4, "{}", hex);
+        assert_eq!(epc.get_owned_value().bit_length(), *bits, "{}", hex);
+    }
+
+    let options = DecodeOptions::new().with_unknown_as_raw(true);
+    // A raw EPC with a known header uses the length of its encoding
+    let data = hex::decode("41000000000000000000000000000000000000000000000000000000").unwrap();
+    let epc = decode_binary_with_options(&data, &options).unwrap();
+    assert_eq!(epc.bit_length(), 212);
+    // Otherwise, the length of the data
+    let epc = decode_binary_with_options(&[0xFF, 0x00, 0x00, 0x00], &options).unwrap();
+    assert_eq!(epc.bit_length(), 32);
+}
+
+#[test]
+fn test_to_gs1_opt() {
+    let examples = [
+        (
+            "3074257BF7194E4000001A85",
+            Some("(01) 80614141123458 (21) 6789"),
+        ),
+        ("3174257BF4499602D2000000", Some("(00) 106141412345678908")),
+        (
+            "3376451FD40C0E400000162E",
+            Some("(8003) 095211411234545678"),
+        ),
+        // GID, US DoD and CPI identifiers have no GS1 element string
+        ("3500E86F8000A9E000000586", None),
+        ("2F320434147455900000162E", None),
+        ("3C74257BF406329C80001A85", None),
+    ];
+    for (hex, gs1) in examples.iter() {
+        let epc = decode_hex(hex).unwrap();
+        assert_eq!(epc.to_gs1_opt().as_deref(), *gs1, "{}", hex);
+    }
+}
+
+#[test]
+fn test_decode_binary_with_pc() {
+    let uri = "urn:epc:id:sgtin:0614141.812345.6789";
+    let examples = [
+        // PC word with a length of 6 words
+        "30003074257BF7194E4000001A85",
+        // Trailing CRC
+        "30003074257BF7194E4000001A85ABCD",
+        // XI bit set, followed by XPC_W1
+        "320000003074257BF7194E4000001A85",
+        // XI bit set, and XEB set in XPC_W1, followed by XPC_W2
+        "3200800000003074257BF7194E4000001A85",
+    ];
+    for example in examples.iter() {
+        let data = hex::decode(example).unwrap();
+        assert_eq!(decode_binary_with_pc(&data).unwrap().to_uri(), uri);
+    }
+
+    // T bit set, indicating an ISO UII
+    let data = hex::decode("31003074257BF7194E4000001A85").unwrap();
+    assert_eq!(decode_binary_with_pc(&data).err(), Some(Gs1Error::Parse));
+
+    // The PC word says 7 words, but SGTIN-96 is 6 words
+    let data = hex::decode("38003074257BF7194E4000001A850000").unwrap();
+    assert_eq!(
+        decode_binary_with_pc(&data).err(),
+        Some(Gs1Error::LengthMismatch { pc: 7, header: 6 })
+    );
+    // The PC word says 5 words, which would truncate an SGTIN-96
+    let data = hex::decode("28003074257BF7194E4000001A85").unwrap();
+    assert_eq!(
+        decode_binary_with_pc(&data).err(),
+        Some(Gs1Error::LengthMismatch { pc: 5, header: 6 })
+    );
+    // SGTIN-198 is rounded up to 13 words
+    let data = hex::decode("68003674257BF6B7A659B2C2BF100000000000000000000000000000").unwrap();
+    assert_eq!(
+        decode_binary_with_pc(&data).unwrap().to_uri(),
+        "urn:epc:id:sgtin:0614141.712345.32a%2Fb"
+    );
+
+    // The PC word says 6 words, but only 5 follow
+    let data = hex::decode("30003074257BF7194E400000").unwrap();
+    assert_eq!(
+        decode_binary_with_pc(&data).err(),
+        Some(Gs1Error::TooShort {
+            needed: 14,
+            got: 12
+        })
+    );
+    assert_eq!(
+        decode_binary_with_pc(&[0x30]).err(),
+        Some(Gs1Error::TooShort { needed: 2, got: 1 })
+    );
+}
+
+#[test]
+fn test_partition_for_company_digits() {
+    // Partition values from the E.3 examples
+    let examples = [
+        (EPCBinaryHeader::SGTIN96, 7, 5),
+        (EPCBinaryHeader::SGTIN198, 7, 5),
+        (EPCBinaryHeader::SSCC96, 7, 5),
+        (EPCBinaryHeader::SSCC96, 6, 6),
+        (EPCBinaryHeader::GRAI96, 7, 5),
+        (EPCBinaryHeader::GIAI96, 12, 0),
+    ];
+    for (scheme, company_digits, partition) in examples.iter() {
+        assert_eq!(
+            partition_for_company_digits(*scheme, *company_digits).unwrap(),
+            *partition
+        );
+    }
+
+    assert_eq!(
+        partition_for_company_digits(EPCBinaryHeader::SGTIN96, 13),
+        Err(Gs1Error::OutOfRange("company_digits"))
+    );
+    assert_eq!(
+        partition_for_company_digits(EPCBinaryHeader::GID96, 7),
+        Err(Gs1Error::Unimplemented(EPCBinaryHeader::GID96))
+    );
+}
+
+#[test]
+fn test_decode_binary_strict() {
+    // Conforming encodings decode as normal
+    for data in [
+        "3074257BF7194E4000001A85",
+        "3174257BF4499602D2000000",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000000",
+        "3776451FD40C0E59B2C2BF1000000000000000000000",
+        "3E74257BF460730A7CC5ED1900000000000000000000",
+    ]
+    .iter()
+    {
+        let data = hex::decode(data).unwrap();
+        assert_eq!(
+            decode_binary_strict(&data).unwrap().get_value(),
+            decode_binary(&data).unwrap().get_value()
+        );
+    }
+
+    // Reserved bits in an SSCC-96, and padding after an SGTIN-198
+    for data in [
+        "3174257BF4499602D2800000",
+        "3674257BF6B7A659B2C2BF100000000000000000000000000001",
+    ]
+    .iter()
+    {
+        let data = hex::decode(data).unwrap();
+        assert!(decode_binary(&data).is_ok());
+        assert_eq!(decode_binary_strict(&data).err(), Some(Gs1Error::Parse));
+        let options = DecodeOptions::new().with_strict(true);
+        assert_eq!(
+            decode_binary_with_options(&data, &options).err(),
+            Some(Gs1Error::Parse)
+        );
+        let options = options.with_strict(false);
+        assert!(decode_binary_with_options(&data, &options).is_ok());
+    }
+}
+
+#[test]
+fn test_same_identity() {
+    let sgtin96 = decode_hex("3074257BF7194E4000001A85").unwrap();
+    let sgtin198 = from_tag_uri("urn:epc:tag:sgtin-198:1.0614141.812345.6789").unwrap();
+    assert_eq!(
+        sgtin96.pure_identity(),
+        "urn:epc:id:sgtin:0614141.812345.6789"
+    );
+    assert_eq!(sgtin96.pure_identity(), sgtin198.pure_identity());
+    assert!(same_identity(sgtin96.as_ref(), sgtin198.as_ref()));
+
+    let other = from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.6790").unwrap();
+    assert!(!same_identity(sgtin96.as_ref(), other.as_ref()));
+    let sscc = decode_hex("3174257BF4499602D2000000").unwrap();
+    assert!(!same_identity(sgtin96.as_ref(), sscc.as_ref()));
+}
+
+#[test]
+fn test_decode_u128() {
+    for data in [
+        "3074257BF7194E4000001A85",
+        "3174257BF4499602D2000000",
+        "3500E86F8000A9E000000586",
+    ]
+    .iter()
+    {
+        let value = u128::from_str_radix(data, 16).unwrap();
+        assert_eq!(
+            decode_u128(value, 96).unwrap().get_value(),
+            decode_binary(&hex::decode(data).unwrap())
+                .unwrap()
+                .get_value()
+        );
+    }
+
+    // ITIP-110 isn't a whole number of bytes
+    let data = hex::decode("4014F4E4E40C0E40820000000F6C").unwrap();
+    let value = u128::from_be_bytes([&[0u8; 2][..], &data[..]].concat().try_into().unwrap()) >> 2;
+    assert_eq!(
+        decode_u128(value, 110).unwrap().get_value(),
+        decode_binary(&data).unwrap().get_value()
+    );
+
+    assert_eq!(
+        decode_u128(1 << 96, 96).err(),
+        Some(Gs1Error::OutOfRange("value"))
+    );
+    assert_eq!(
+        decode_u128(0, 129).err(),
+        Some(Gs1Error::OutOfRange("bits"))
+    );
+}
+
+#[test]
+fn test_epc_list() {
+    let sgtin = hex::decode("3074257BF7194E4000001A85").unwrap();
+    let gid = hex::decode("3500E86F8000A9E000000586").unwrap();
+    let tags: [&[u8]; 5] = [&sgtin, &[0; 12], &sgtin[..6], &gid, &sgtin];
+    assert_eq!(
+        epc_list(&tags),
+        vec![
+            "urn:epc:id:sgtin:0614141.812345.6789",
+            "urn:epc:id:gid:952056.2718.1414",
+            "urn:epc:id:sgtin:0614141.812345.6789",
+        ]
+    );
+    assert!(epc_list(&[]).is_empty());
+}
+
+#[test]
+fn test_uri_leading_zero_company() {
+    // The leading zeros of the company prefix determine its length, so they must be preserved
+    let epc = from_uri("urn:epc:id:sgtin:0614141.812345.6789").unwrap();
+    match epc.get_value() {
+        EPCValue::SGTIN96(val) => {
+            assert_eq!(val.gtin.company, 614141);
+            assert_eq!(val.gtin.company_digits, 7);
+        }
+        _ => panic!("Invalid type"),
+    }
+    assert_eq!(epc.to_uri(), "urn:epc:id:sgtin:0614141.812345.6789");
+
+    for uri in [
+        "urn:epc:id:sgtin:000614.1812345.6789",
+        "urn:epc:id:sscc:0614141.1234567890",
+        "urn:epc:id:sscc:00614141.123456789",
+        "urn:epc:id:grai:0614141.12345.400",
+        "urn:epc:id:grai:00614141.1234.400",
+    ]
+    .iter()
+    {
+        let epc = from_uri(uri).unwrap();
+        assert_eq!(epc.to_uri(), *uri);
+        // The partition survives a round trip through the tag URI too
+        let epc = from_tag_uri(&epc.to_tag_uri()).unwrap();
+        assert_eq!(epc.to_uri(), *uri);
+    }
+}
+
+#[test]
+fn test_sscc_element_string() {
+    // The element string is 18 digits whatever the length of the company prefix
+    for company_digits in 6..=12 {
+        let sscc = SSCC96::from_sscc("106141412345678908", company_digits).unwrap();
+        assert_eq!(sscc.partition as usize, 12 - company_digits);
+        assert_eq!(sscc.to_sscc().unwrap(), "106141412345678908");
+        assert_eq!(sscc.to_gs1(), "(00) 106141412345678908");
+    }
+
+    let sscc = SSCC96::from_sscc("106141412345678908", 7).unwrap();
+    let mut invalid = sscc.clone();
+    invalid.partition = 7;
+    assert_eq!(invalid.to_sscc(), Err(Gs1Error::InvalidPartition(7)));
+
+    let mut invalid = sscc.clone();
+    invalid.company = 10_000_000;
+    assert_eq!(invalid.to_sscc(), Err(Gs1Error::OutOfRange("company")));
+
+    let mut invalid = sscc.clone();
+    invalid.serial = 1_000_000_000;
+    assert_eq!(invalid.to_sscc(), Err(Gs1Error::OutOfRange("serial")));
+}
+
+#[test]
+fn test_binary_to_uri() {
+    let data = hex::decode("3500E86F8000A9E000000586").unwrap();
+    assert_eq!(
+        binary_to_uri(&data).unwrap(),
+        "urn:epc:id:gid:952056.2718.1414"
+    );
+    assert_eq!(
+        binary_to_uri(&data[..6]),
+        Err(Gs1Error::TooShort { needed: 12, got: 6 })
+    );
+    assert_eq!(
+        binary_to_uri(&[0x2E; 12]).unwrap(),
+        "urn:epc:raw:96.x2E2E2E2E2E2E2E2E2E2E2E2E"
+    );
+    assert_eq!(binary_to_uri(&[0xE2; 12]), Err(Gs1Error::Parse));
 }
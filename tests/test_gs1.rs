@@ -0,0 +1,899 @@
+use gs1::barcode::to_gs1_128;
+use gs1::digital_link::{decompress_gtin_serial, to_digital_link};
+use gs1::epc::sgtin::{SGTIN198, SGTIN96};
+use gs1::epc::sscc::{LogisticUnit, SSCC96};
+use gs1::error::Gs1Error;
+use gs1::gln::GLN;
+use gs1::{
+    combine_gs1, parse_element_string, AiSpec, ApplicationIdentifier, ElementStringBuilder, FNC1,
+    GS1, GTIN,
+};
+
+#[test]
+fn test_combine_gs1() {
+    let sscc = SSCC96 {
+        filter: 3,
+        partition: 5,
+        indicator: 1,
+        company: 614141,
+        serial: 234567890,
+    };
+    let sgtin = SGTIN96 {
+        filter: 3,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        },
+        serial: 6789,
+    };
+
+    // The variable-length serial is moved to the end, so no FNC1 is needed
+    assert_eq!(
+        combine_gs1(&[&sgtin, &sscc]).unwrap(),
+        "018061414112345800106141412345678908216789"
+    );
+
+    // Repeated elements are only included once
+    assert_eq!(
+        combine_gs1(&[&sscc, &sgtin, &sgtin.gtin]).unwrap(),
+        "001061414123456789080180614141123458216789"
+    );
+
+    // Conflicting values for the same AI are an error
+    let other = SGTIN96 {
+        filter: 3,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        },
+        serial: 1234,
+    };
+    assert!(combine_gs1(&[&sgtin, &other]).is_err());
+}
+
+// A company internal or mutually agreed element, which isn't interpreted
+struct Internal(u8, String);
+
+impl GS1 for Internal {
+    fn to_gs1(&self) -> String {
+        format!("({}) {}", self.0, self.1)
+    }
+}
+
+#[test]
+fn test_combine_gs1_internal_ai() {
+    let gtin = GTIN {
+        company: 614141,
+        company_digits: 7,
+        item: 12345,
+        indicator: 8,
+    };
+    assert_eq!(
+        combine_gs1(&[&Internal(91, "ABC-123/4".to_string()), &gtin]).unwrap(),
+        "018061414112345891ABC-123/4"
+    );
+
+    // AI 90 is limited to 30 characters, and AIs 91-99 to 90
+    assert!(combine_gs1(&[&Internal(90, "A".repeat(30))]).is_ok());
+    assert!(combine_gs1(&[&Internal(90, "A".repeat(31))]).is_err());
+    assert!(combine_gs1(&[&Internal(91, "A".repeat(90))]).is_ok());
+    assert!(combine_gs1(&[&Internal(91, "A".repeat(91))]).is_err());
+    // Values must be from character set 82
+    assert!(combine_gs1(&[&Internal(91, "AB#12".to_string())]).is_err());
+}
+
+#[test]
+fn test_gtin_is_globally_unique() {
+    let gtin = GTIN {
+        company: 614141,
+        company_digits: 7,
+        item: 12345,
+        indicator: 8,
+    };
+    assert!(gtin.is_globally_unique());
+
+    // In-store GTIN-13 with prefix 21
+    let gtin = GTIN {
+        company: 2112345,
+        company_digits: 7,
+        item: 12345,
+        indicator: 0,
+    };
+    assert!(!gtin.is_globally_unique());
+
+    // UPC-A with number system 2, which is prefix 02 as a GTIN-13
+    let gtin = GTIN {
+        company: 212345,
+        company_digits: 7,
+        item: 67890,
+        indicator: 0,
+    };
+    assert!(!gtin.is_globally_unique());
+
+    // RCN-8s have the GS1-8 prefix 0 or 2, but other GTIN-8s are unique
+    for (rcn8, unique) in [("02345673", false), ("22345677", false), ("95012346", true)].iter() {
+        let gtin: GTIN = rcn8.parse().unwrap();
+        assert_eq!(gtin.is_globally_unique(), *unique);
+    }
+}
+
+#[test]
+fn test_sscc_from_sscc() {
+    let sscc = SSCC96::from_sscc("106141412345678908", 7).unwrap();
+    assert_eq!(
+        sscc,
+        SSCC96 {
+            filter: 0,
+            partition: 5,
+            indicator: 1,
+            company: 614141,
+            serial: 234567890,
+        }
+    );
+    assert_eq!(sscc.to_gs1(), "(00) 106141412345678908");
+
+    // The company prefix length changes how the digits are split
+    let sscc = SSCC96::from_sscc("106141412345678908", 9).unwrap();
+    assert_eq!(sscc.company, 61414123);
+    assert_eq!(sscc.serial, 4567890);
+    assert_eq!(sscc.to_gs1(), "(00) 106141412345678908");
+
+    assert_eq!(
+        SSCC96::from_sscc("106141412345678909", 7),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!(
+        SSCC96::from_sscc("10614141234567890", 7),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        SSCC96::from_sscc("1061414123456789O8", 7),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        SSCC96::from_sscc("106141412345678908", 5),
+        Err(Gs1Error::OutOfRange("company_digits"))
+    );
+}
+
+#[test]
+fn test_logistic_unit() {
+    let sscc = SSCC96 {
+        filter: 2,
+        partition: 5,
+        indicator: 1,
+        company: 614141,
+        serial: 234567890,
+    };
+    let gtin = GTIN {
+        company: 614141,
+        company_digits: 7,
+        item: 12345,
+        indicator: 8,
+    };
+
+    let unit = LogisticUnit::new(sscc).with_content(gtin, 24).unwrap();
+    assert_eq!(
+        unit.to_gs1(),
+        "(00) 106141412345678908 (02) 80614141123458 (37) 24"
+    );
+    assert_eq!(
+        combine_gs1(&[&unit]).unwrap(),
+        "0010614141234567890802806141411234583724"
+    );
+}
+
+#[test]
+fn test_digital_link_compressed() {
+    let sgtin = SGTIN96 {
+        filter: 3,
+        gtin: GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        },
+        serial: 6789,
+    };
+    let uri = sgtin
+        .to_digital_link_compressed("https://id.gs1.org")
+        .unwrap();
+    assert_eq!(uri, "https://id.gs1.org/AZKi4n23BEII1Cg");
+    assert_eq!(
+        decompress_gtin_serial(&uri).unwrap(),
+        ("80614141123458".to_string(), "6789".to_string())
+    );
+}
+
+#[test]
+fn test_gtin_from_str() {
+    let gtin: GTIN = "80614141123458".parse().unwrap();
+    assert_eq!(
+        gtin,
+        GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        }
+    );
+    assert_eq!(gtin.to_string(), "(01) 80614141123458");
+    assert_eq!(gtin.to_string(), gtin.to_gs1());
+    assert_eq!(gtin.to_string().parse::<GTIN>().unwrap(), gtin);
+
+    // Shorter GTINs are zero-padded, with an indicator of 0
+    for (input, gtin14) in [
+        ("96385074", "00000096385074"),
+        ("036000291452", "00036000291452"),
+        ("5901234123457", "05901234123457"),
+    ]
+    .iter()
+    {
+        let gtin: GTIN = input.parse().unwrap();
+        assert_eq!(gtin.indicator, 0);
+        assert_eq!(gtin.to_gtin14(), *gtin14);
+    }
+
+    assert_eq!(
+        "5901234123458".parse::<GTIN>(),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!(
+        "590123412345".parse::<GTIN>(),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!("59012341234".parse::<GTIN>(), Err(Gs1Error::Parse));
+    assert_eq!("59012341234a7".parse::<GTIN>(), Err(Gs1Error::Parse));
+}
+
+#[test]
+fn test_gtin_new() {
+    let gtin = GTIN::new(614141, 7, 12345, 8).unwrap();
+    assert_eq!(
+        gtin,
+        GTIN {
+            company: 614141,
+            company_digits: 7,
+            item: 12345,
+            indicator: 8,
+        }
+    );
+    assert_eq!(gtin.to_gs1(), "(01) 80614141123458");
+
+    // A 12-digit company prefix leaves no digits for the item reference
+    assert!(GTIN::new(123456789012, 12, 0, 0).is_ok());
+    assert_eq!(
+        GTIN::new(123456789012, 12, 1, 0),
+        Err(Gs1Error::OutOfRange("item"))
+    );
+
+    assert_eq!(
+        GTIN::new(614141, 13, 0, 0),
+        Err(Gs1Error::OutOfRange("company_digits"))
+    );
+    assert_eq!(
+        GTIN::new(10_000_000, 7, 12345, 0),
+        Err(Gs1Error::OutOfRange("company"))
+    );
+    assert_eq!(
+        GTIN::new(614141, 7, 12345, 10),
+        Err(Gs1Error::OutOfRange("indicator"))
+    );
+}
+
+#[test]
+fn test_gtin_to_gtin14() {
+    // UPC-A
+    let gtin: GTIN = "036000291452".parse().unwrap();
+    assert_eq!(gtin.to_gtin14(), "00036000291452");
+    // EAN-13
+    let gtin: GTIN = "4006381333931".parse().unwrap();
+    assert_eq!(gtin.to_gtin14(), "04006381333931");
+
+    let gtin = GTIN {
+        company: 614141,
+        company_digits: 7,
+        item: 12345,
+        indicator: 8,
+    };
+    assert_eq!(gtin.to_gtin14(), "80614141123458");
+}
+
+#[test]
+fn test_gtin_with_check_digit() {
+    assert_eq!(GTIN::with_check_digit("9638507").unwrap(), "00000096385074");
+    assert_eq!(
+        GTIN::with_check_digit("03600029145").unwrap(),
+        "00036000291452"
+    );
+    assert_eq!(
+        GTIN::with_check_digit("400638133393").unwrap(),
+        "04006381333931"
+    );
+    assert_eq!(
+        GTIN::with_check_digit("8061414112345").unwrap(),
+        "80614141123458"
+    );
+
+    // Zero-padding the body doesn't change the check digit
+    assert_eq!(
+        GTIN::with_check_digit("0000009638507").unwrap(),
+        "00000096385074"
+    );
+    assert!(GTIN::with_check_digit("03600029145")
+        .unwrap()
+        .parse::<GTIN>()
+        .is_ok());
+}
+
+#[test]
+fn test_gtin_with_check_digit_length() {
+    assert_eq!(GTIN::with_check_digit("12345"), Err(Gs1Error::Parse));
+    assert_eq!(GTIN::with_check_digit(""), Err(Gs1Error::Parse));
+    assert_eq!(
+        GTIN::with_check_digit("80614141123458"),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        GTIN::with_check_digit("806141411234589"),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(GTIN::with_check_digit("963850X"), Err(Gs1Error::Parse));
+}
+
+#[test]
+fn test_gtin_from_upc_a_ean13() {
+    let gtin = GTIN::from_upc_a("036000291452").unwrap();
+    assert_eq!(gtin.indicator, 0);
+    assert_eq!(gtin.to_gtin14(), "00036000291452");
+    // The same item as an EAN-13
+    assert_eq!(GTIN::from_ean13("0036000291452").unwrap(), gtin);
+
+    assert_eq!(
+        GTIN::from_upc_a("036000291453"),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!(GTIN::from_upc_a("0036000291452"), Err(Gs1Error::Parse));
+
+    let gtin = GTIN::from_ean13("4006381333931").unwrap();
+    assert_eq!(gtin.to_gtin14(), "04006381333931");
+    assert_eq!(
+        GTIN::from_ean13("4006381333932"),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!(GTIN::from_ean13("036000291452"), Err(Gs1Error::Parse));
+}
+
+#[test]
+fn test_gtin_isbn_issn() {
+    let gtin = GTIN::from_ean13("9780306406157").unwrap();
+    assert_eq!(gtin.isbn(), Some("9780306406157".to_string()));
+    assert_eq!(gtin.isbn10(), Some("0306406152".to_string()));
+    assert_eq!(gtin.issn(), None);
+
+    // The ISBN-10 check digit can be X
+    let gtin = GTIN::from_ean13("9780804429573").unwrap();
+    assert_eq!(gtin.isbn10(), Some("080442957X".to_string()));
+
+    let gtin = GTIN::from_ean13("9791012345678").unwrap();
+    assert_eq!(gtin.isbn(), Some("9791012345678".to_string()));
+    assert_eq!(gtin.isbn10(), None);
+
+    let gtin = GTIN::from_ean13("9770317847001").unwrap();
+    assert_eq!(gtin.issn(), Some("0317-8471".to_string()));
+    assert_eq!(gtin.isbn(), None);
+
+    // GTINs with an indicator digit aren't books or serials
+    let gtin: GTIN = "19780306406154".parse().unwrap();
+    assert_eq!(gtin.isbn(), None);
+
+    let gtin = GTIN::from_ean13("4006381333931").unwrap();
+    assert_eq!(gtin.isbn(), None);
+    assert_eq!(gtin.issn(), None);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_from_digital_link() {
+    use gs1::from_digital_link;
+
+    let ais = from_digital_link("https://id.gs1.org/01/09506000134352/21/ABC%2F1").unwrap();
+    assert_eq!(ais.len(), 2);
+    assert_eq!(ais[&ApplicationIdentifier::GTIN], "09506000134352");
+    assert_eq!(ais[&ApplicationIdentifier::SerialNumber], "ABC/1");
+
+    // Custom domain, with data attributes in the query string
+    let ais = from_digital_link(
+        "https://example.com/01/09506000134352/10/AB-123?17=290101&linkType=gs1:pip",
+    )
+    .unwrap();
+    assert_eq!(ais.len(), 3);
+    assert_eq!(ais[&ApplicationIdentifier::Batch], "AB-123");
+    assert_eq!(ais[&ApplicationIdentifier::ExpirationDate], "290101");
+
+    // Custom domain with a path prefix before the primary key
+    let ais = from_digital_link("https://example.com/a/b/c/01/09506000134352/21/ABC").unwrap();
+    assert_eq!(ais.len(), 2);
+    assert_eq!(ais[&ApplicationIdentifier::SerialNumber], "ABC");
+
+    // Odd number of path segments
+    assert!(from_digital_link("https://example.com/01/09506000134352/21").is_err());
+    assert!(from_digital_link("https://example.com/").is_err());
+    // Non-numeric AI
+    assert!(from_digital_link("https://example.com/gtin/09506000134352").is_err());
+    assert!(from_digital_link("example.com/01/09506000134352").is_err());
+}
+
+#[test]
+fn test_parse_digital_link() {
+    use gs1::parse_digital_link;
+
+    assert_eq!(
+        parse_digital_link("https://example.com/dl/01/09506000134352/10/AB-123?17=290101").unwrap(),
+        vec![
+            (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+            (ApplicationIdentifier::Batch, "AB-123".to_string()),
+            (ApplicationIdentifier::ExpirationDate, "290101".to_string()),
+        ]
+    );
+    // The prefix may itself look like an AI
+    assert_eq!(
+        parse_digital_link("https://example.com/21/01/09506000134352").unwrap(),
+        vec![(ApplicationIdentifier::GTIN, "09506000134352".to_string())]
+    );
+
+    // No primary key in the path
+    assert!(parse_digital_link("https://example.com/10/AB-123").is_err());
+    assert!(parse_digital_link("https://example.com/dl/").is_err());
+}
+
+#[test]
+fn test_parse_element_string() {
+    let expected = vec![
+        (ApplicationIdentifier::GTIN, "80614141123458".to_string()),
+        (ApplicationIdentifier::SerialNumber, "6789".to_string()),
+    ];
+    assert_eq!(
+        parse_element_string("(01) 80614141123458 (21) 6789").unwrap(),
+        expected
+    );
+    assert_eq!(
+        parse_element_string("0180614141123458216789").unwrap(),
+        expected
+    );
+    // Symbology identifier and leading FNC1
+    assert_eq!(
+        parse_element_string(&format!("]C1{}0180614141123458216789", FNC1)).unwrap(),
+        expected
+    );
+
+    // Variable-length elements are separated by FNC1, and internal AIs are left uninterpreted
+    let input = format!("8003095211411234545678{}91ABC-123{}17290101", FNC1, FNC1);
+    assert_eq!(
+        parse_element_string(&input).unwrap(),
+        vec![
+            (
+                ApplicationIdentifier::GRAI,
+                "095211411234545678".to_string()
+            ),
+            (
+                ApplicationIdentifier::CompanyInternal1,
+                "ABC-123".to_string()
+            ),
+            (ApplicationIdentifier::ExpirationDate, "290101".to_string()),
+        ]
+    );
+
+    // The output of combine_gs1 can be parsed
+    let sgtin = SGTIN96 {
+        filter: 3,
+        gtin: "80614141123458".parse().unwrap(),
+        serial: 6789,
+    };
+    let unit = LogisticUnit::new(SSCC96 {
+        filter: 3,
+        partition: 5,
+        indicator: 1,
+        company: 614141,
+        serial: 234567890,
+    });
+    let combined = combine_gs1(&[&sgtin, &unit]).unwrap();
+    assert_eq!(parse_element_string(&combined).unwrap().len(), 3);
+
+    // Measures have a predefined length, and prices are variable-length
+    let expected = vec![
+        (ApplicationIdentifier::GTIN, "90614141000015".to_string()),
+        (ApplicationIdentifier::NetWeightKg3, "000123".to_string()),
+        (ApplicationIdentifier::Price2, "1234".to_string()),
+    ];
+    assert_eq!(
+        parse_element_string("(01) 90614141000015 (3103) 000123 (3922) 1234").unwrap(),
+        expected
+    );
+    assert_eq!(
+        parse_element_string("0190614141000015310300012339221234").unwrap(),
+        expected
+    );
+    assert!(parse_element_string("(3103) 00012").is_err());
+    // There are no measure AIs with more than 5 decimal places
+    assert!(parse_element_string("(3106) 000123").is_err());
+
+    // Truncated predefined-length element
+    assert!(parse_element_string("01806141411234").is_err());
+    // Unknown AI
+    assert!(parse_element_string("(23) 1234").is_err());
+    assert!(parse_element_string("").unwrap().is_empty());
+}
+
+#[test]
+fn test_ai_spec() {
+    let spec = ApplicationIdentifier::GTIN.spec();
+    assert_eq!(
+        spec,
+        AiSpec {
+            fixed_length: true,
+            max_length: 14,
+            numeric: true,
+        }
+    );
+    assert!(spec.is_valid("80614141123458"));
+    assert!(!spec.is_valid("8061414112345"));
+    assert!(!spec.is_valid("806141411234AB"));
+
+    let spec = ApplicationIdentifier::SerialNumber.spec();
+    assert!(!spec.fixed_length);
+    assert!(!spec.numeric);
+    assert!(spec.is_valid("32a/b"));
+    assert!(!spec.is_valid(""));
+    assert!(!spec.is_valid("012345678901234567890"));
+
+    assert_eq!(ApplicationIdentifier::ContentCount.spec().max_length, 8);
+    assert_eq!(
+        ApplicationIdentifier::LogisticVolumeYd35.spec(),
+        AiSpec {
+            fixed_length: true,
+            max_length: 6,
+            numeric: true,
+        }
+    );
+    assert_eq!(ApplicationIdentifier::PriceISO0.spec().max_length, 18);
+    assert_eq!(
+        "3695".parse(),
+        Ok(ApplicationIdentifier::LogisticVolumeYd35)
+    );
+    assert_eq!("8003".parse(), Ok(ApplicationIdentifier::GRAI));
+    assert!("003".parse::<ApplicationIdentifier>().is_err());
+
+    // Values are checked against the spec when parsing and combining
+    assert!(parse_element_string("(17) 2901").is_err());
+    let sgtin = SGTIN198 {
+        filter: 3,
+        gtin: "80614141123458".parse().unwrap(),
+        serial: "012345678901234567890".to_string(),
+    };
+    assert!(combine_gs1(&[&sgtin]).is_err());
+}
+
+#[test]
+fn test_to_gs1_128() {
+    let data = to_gs1_128(&[
+        (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+        (ApplicationIdentifier::Batch, "ABC123".to_string()),
+        (ApplicationIdentifier::ExpirationDate, "251231".to_string()),
+    ]);
+    assert_eq!(data, format!("010950600013435210ABC123{}17251231", FNC1));
+
+    // Variable-length elements are followed by FNC1 wherever they appear
+    let data = to_gs1_128(&[
+        (ApplicationIdentifier::Batch, "ABC123".to_string()),
+        (ApplicationIdentifier::GRAI, "0950600013435212".to_string()),
+        (ApplicationIdentifier::ExpirationDate, "251231".to_string()),
+    ]);
+    assert_eq!(
+        data,
+        format!("10ABC123{}80030950600013435212{}17251231", FNC1, FNC1)
+    );
+    assert_eq!(to_gs1_128(&[]), "");
+}
+
+#[test]
+fn test_element_string_builder() {
+    let mut builder = ElementStringBuilder::new();
+    builder
+        .add(ApplicationIdentifier::GTIN, "09506000134352")
+        .unwrap()
+        .add(ApplicationIdentifier::Batch, "ABC123")
+        .unwrap()
+        .add(ApplicationIdentifier::BestBeforeDate, "290131")
+        .unwrap()
+        .add(ApplicationIdentifier::SerialNumber, "6789")
+        .unwrap();
+    assert_eq!(
+        builder.build(),
+        format!("010950600013435210ABC123{}15290131216789", FNC1)
+    );
+    assert_eq!(
+        builder.to_gs1(),
+        "(01) 09506000134352 (10) ABC123 (15) 290131 (21) 6789"
+    );
+    // Both forms parse back to the same elements
+    assert_eq!(
+        parse_element_string(&builder.build()).unwrap(),
+        builder.elements()
+    );
+    assert_eq!(
+        parse_element_string(&builder.to_gs1()).unwrap(),
+        builder.elements()
+    );
+
+    // Values which don't match their AI, and repeated AIs, are rejected
+    assert_eq!(
+        builder.add(ApplicationIdentifier::Batch, "DEF456").err(),
+        Some(Gs1Error::Parse)
+    );
+    assert!(builder
+        .add(ApplicationIdentifier::ExpirationDate, "2901")
+        .is_err());
+    assert!(builder
+        .add(ApplicationIdentifier::ContentCount, "123456789")
+        .is_err());
+    assert_eq!(builder.elements().len(), 4);
+
+    assert_eq!(ElementStringBuilder::new().build(), "");
+}
+
+#[test]
+fn test_to_digital_link() {
+    let ais = [
+        (ApplicationIdentifier::Batch, "ABC123".to_string()),
+        (ApplicationIdentifier::ExpirationDate, "251231".to_string()),
+        (ApplicationIdentifier::SerialNumber, "12345".to_string()),
+        (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+    ];
+    assert_eq!(
+        to_digital_link(&ais, "https://example.com/").unwrap(),
+        "https://example.com/01/09506000134352/10/ABC123/21/12345?17=251231"
+    );
+
+    // Only the qualifiers of the primary key are placed in the path
+    let ais = [
+        (
+            ApplicationIdentifier::SSCC,
+            "106141412345678908".to_string(),
+        ),
+        (ApplicationIdentifier::Batch, "A&B".to_string()),
+        (ApplicationIdentifier::ContentCount, "20".to_string()),
+    ];
+    assert_eq!(
+        to_digital_link(&ais, "https://id.gs1.org").unwrap(),
+        "https://id.gs1.org/00/106141412345678908?10=A%26B&37=20"
+    );
+
+    // No primary key
+    let ais = [(ApplicationIdentifier::Batch, "ABC123".to_string())];
+    assert_eq!(
+        to_digital_link(&ais, "https://id.gs1.org"),
+        Err(Gs1Error::Parse)
+    );
+    // Invalid value
+    let ais = [(ApplicationIdentifier::GTIN, "0950600013435".to_string())];
+    assert_eq!(
+        to_digital_link(&ais, "https://id.gs1.org"),
+        Err(Gs1Error::Parse)
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_digital_link_gsrn() {
+    use gs1::from_digital_link;
+
+    // The GSRNs are primary keys, qualified by the service relation instance number
+    let ais = [
+        (
+            ApplicationIdentifier::ServiceRelationInstance,
+            "12".to_string(),
+        ),
+        (
+            ApplicationIdentifier::GSRNRecipient,
+            "061414112345678902".to_string(),
+        ),
+    ];
+    let uri = to_digital_link(&ais, "https://id.gs1.org").unwrap();
+    assert_eq!(uri, "https://id.gs1.org/8018/061414112345678902/8019/12");
+    let parsed = from_digital_link(&uri).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(
+        parsed[&ApplicationIdentifier::GSRNRecipient],
+        "061414112345678902"
+    );
+    assert_eq!(
+        parsed[&ApplicationIdentifier::ServiceRelationInstance],
+        "12"
+    );
+
+    let ais = [(
+        ApplicationIdentifier::GSRNProvider,
+        "061414112345678902".to_string(),
+    )];
+    let uri = to_digital_link(&ais, "https://id.gs1.org").unwrap();
+    assert_eq!(uri, "https://id.gs1.org/8017/061414112345678902");
+    assert_eq!(
+        from_digital_link(&uri).unwrap()[&ApplicationIdentifier::GSRNProvider],
+        "061414112345678902"
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_digital_link_roundtrip() {
+    use gs1::from_digital_link;
+
+    let ais = [
+        (ApplicationIdentifier::GTIN, "09506000134352".to_string()),
+        (ApplicationIdentifier::SerialNumber, "A/B?C".to_string()),
+        (ApplicationIdentifier::ExpirationDate, "251231".to_string()),
+    ];
+    let parsed = from_digital_link(&to_digital_link(&ais, "https://id.gs1.org").unwrap()).unwrap();
+    assert_eq!(parsed.len(), ais.len());
+    for (ai, value) in ais.iter() {
+        assert_eq!(&parsed[ai], value);
+    }
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_parse_date_ai() {
+    use chrono::NaiveDate;
+    use gs1::date::parse_date_ai_at;
+
+    let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let examples = [
+        ("290131", date(2029, 1, 31)),
+        // A day of 00 is the last day of the month
+        ("251200", date(2025, 12, 31)),
+        ("240200", date(2024, 2, 29)),
+        ("250200", date(2025, 2, 28)),
+        // Dates more than 49 years ahead are in the previous century
+        ("750101", date(2075, 1, 1)),
+        ("770101", date(1977, 1, 1)),
+    ];
+    for (value, expected) in examples.iter() {
+        assert_eq!(
+            parse_date_ai_at(ApplicationIdentifier::ExpirationDate, value, today).unwrap(),
+            *expected
+        );
+    }
+
+    // Dates more than 50 years in the past are in the next century
+    let today = NaiveDate::from_ymd_opt(2080, 1, 1).unwrap();
+    assert_eq!(
+        parse_date_ai_at(ApplicationIdentifier::BestBeforeDate, "300101", today).unwrap(),
+        date(2130, 1, 1)
+    );
+
+    for value in ["251301", "250001", "250230", "25010", "2501011", "25O101"].iter() {
+        assert_eq!(
+            parse_date_ai_at(ApplicationIdentifier::ExpirationDate, value, today),
+            Err(Gs1Error::Parse),
+            "{}",
+            value
+        );
+    }
+    assert_eq!(
+        parse_date_ai_at(ApplicationIdentifier::Batch, "250101", today),
+        Err(Gs1Error::Parse)
+    );
+
+    #[cfg(feature = "std")]
+    assert!(gs1::parse_date_ai(ApplicationIdentifier::ProductionDate, "250101").is_ok());
+}
+
+#[test]
+fn test_gtin_parse_with_prefix() {
+    let gtin = GTIN::parse_with_prefix("80614141123458", 7).unwrap();
+    assert_eq!(gtin, GTIN::new(614141, 7, 12345, 8).unwrap());
+    assert_eq!(gtin.to_gtin14(), "80614141123458");
+
+    // GTIN-12s and GTIN-13s have an indicator of 0
+    let gtin = GTIN::parse_with_prefix("036000291452", 8).unwrap();
+    assert_eq!(gtin, GTIN::new(360002, 8, 9145, 0).unwrap());
+
+    // A 12-digit company prefix leaves no item reference
+    let gtin = GTIN::parse_with_prefix("80614141123458", 12).unwrap();
+    assert_eq!(gtin, GTIN::new(61414112345, 12, 0, 8).unwrap());
+    assert_eq!(gtin.to_gtin14(), "80614141123458");
+
+    assert_eq!(
+        GTIN::parse_with_prefix("80614141123459", 7),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!(
+        GTIN::parse_with_prefix("8061414112", 7),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        GTIN::parse_with_prefix("806141411234581", 7),
+        Err(Gs1Error::Parse)
+    );
+    assert_eq!(
+        GTIN::parse_with_prefix("80614141123458", 13),
+        Err(Gs1Error::OutOfRange("company_digits"))
+    );
+}
+
+#[test]
+fn test_gln() {
+    let gln: GLN = "0614141000005".parse().unwrap();
+    assert_eq!(
+        gln,
+        GLN {
+            company: 614141,
+            company_digits: 7,
+            location: 0,
+        }
+    );
+    assert_eq!(gln.to_gln13(), "0614141000005");
+    assert_eq!(gln.to_string(), "(414) 0614141000005");
+
+    // The check digit calculation example from the GS1 General Specifications
+    let gln: GLN = "6291041500213".parse().unwrap();
+    assert_eq!(gln.to_gln13(), "6291041500213");
+
+    assert_eq!(
+        "0614141000006".parse::<GLN>(),
+        Err(Gs1Error::InvalidChecksum)
+    );
+    assert_eq!("061414100000".parse::<GLN>(), Err(Gs1Error::Parse));
+    assert_eq!("061414100000A".parse::<GLN>(), Err(Gs1Error::Parse));
+}
+
+#[test]
+fn test_gtin_set_indicator() {
+    let mut gtin = GTIN::new(614141, 7, 12345, 0).unwrap();
+    assert_eq!(gtin.to_gtin14(), "00614141123452");
+
+    gtin.set_indicator(8).unwrap();
+    assert_eq!(gtin.indicator, 8);
+    assert_eq!(gtin.to_gs1(), "(01) 80614141123458");
+
+    assert_eq!(
+        gtin.set_indicator(10),
+        Err(Gs1Error::OutOfRange("indicator"))
+    );
+    assert_eq!(gtin.indicator, 8);
+
+    gtin.set_indicator(0).unwrap();
+    assert_eq!(gtin.to_gtin14(), "00614141123452");
+
+    // A GTIN parsed from a UPC-A or EAN-8 doesn't know where it came from, so it can be given
+    // an indicator to make the GTIN-14 of a case of that item
+    let mut gtin: GTIN = "614141000036".parse().unwrap();
+    gtin.set_indicator(1).unwrap();
+    assert_eq!(gtin.to_gtin14(), "10614141000033");
+    let mut gtin: GTIN = "95012346".parse().unwrap();
+    gtin.set_indicator(1).unwrap();
+    assert_eq!(gtin.to_gtin14(), "10000095012343");
+}
+
+#[test]
+fn test_gtin_as_ai() {
+    let gtin: GTIN = "036000291452".parse().unwrap();
+    let (ai, value) = gtin.as_ai();
+    assert_eq!(ai, ApplicationIdentifier::GTIN);
+    assert_eq!(value, "00036000291452");
+
+    let mut builder = ElementStringBuilder::new();
+    builder
+        .add(ai, &value)
+        .unwrap()
+        .add(ApplicationIdentifier::SerialNumber, "6789")
+        .unwrap();
+    assert_eq!(builder.to_gs1(), "(01) 00036000291452 (21) 6789");
+    assert_eq!(to_gs1_128(&[gtin.as_ai()]), "0100036000291452");
+}
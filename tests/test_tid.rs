@@ -0,0 +1,183 @@
+use gs1::epc::tid::{
+    decode_tid, decode_xtid_header, decode_xtid_header_strict, decode_xtid_serial, encode_tid,
+    lookup_mdid, lookup_tmid, mdid_from_name, mdid_name, tmid_name, FullTid, TidDecodeState,
+    TidDecoder, TidReader, MDID_TABLE, TID, TMID_TABLE,
+};
+use gs1::error::Gs1Error;
+
+#[test]
+fn test_tid_decoder() {
+    // Impinj Monza 4QT with a 48-bit XTID serial number
+    let data = [
+        0xE2, 0x80, 0x11, 0x05, 0x00, 0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC,
+    ];
+
+    let mut decoder = TidDecoder::new();
+    let mut states = Vec::new();
+    for word in data.chunks(2) {
+        states.push(decoder.feed(word).unwrap());
+    }
+
+    assert_eq!(
+        states[..5],
+        [
+            TidDecodeState::NeedMore(1),
+            TidDecodeState::NeedMore(1),
+            TidDecodeState::NeedMore(3),
+            TidDecodeState::NeedMore(2),
+            TidDecodeState::NeedMore(1),
+        ]
+    );
+
+    let tid = match &states[5] {
+        TidDecodeState::Complete(tid) => tid,
+        _ => panic!("Decoding should be complete"),
+    };
+    assert_eq!(tid.tid, decode_tid(&data[..4]).unwrap());
+    assert_eq!(tid.tid.mdid, 0x1);
+    assert_eq!(tid.tid.tmid, 0x105);
+    assert_eq!(tid.xtid.unwrap().serial_size, 48);
+    assert_eq!(tid.serial, Some(data[6..].to_vec()));
+}
+
+#[test]
+fn test_tid_decoder_no_xtid() {
+    let mut decoder = TidDecoder::new();
+    assert_eq!(
+        decoder.feed(&[0xE2, 0x00, 0x11, 0x05]).unwrap(),
+        TidDecodeState::Complete(FullTid {
+            tid: decode_tid(&[0xE2, 0x00, 0x11, 0x05]).unwrap(),
+            xtid: None,
+            serial: None,
+        })
+    );
+
+    let mut decoder = TidDecoder::new();
+    assert!(decoder.feed(&[0xE1, 0x00, 0x11, 0x05]).is_err());
+}
+
+#[test]
+fn test_decode_xtid_serial() {
+    let data = [0x00, 0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0];
+    let xtid = decode_xtid_header(&data[..2]).unwrap();
+    assert_eq!(xtid.serial_size, 48);
+
+    // Only the serial number is returned, not any following data
+    assert_eq!(
+        decode_xtid_serial(&data[2..], xtid.serial_size).unwrap(),
+        vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]
+    );
+    assert!(decode_xtid_serial(&data[2..6], xtid.serial_size).is_err());
+}
+
+#[test]
+fn test_decode_xtid_header_too_short() {
+    assert_eq!(
+        decode_xtid_header(&[0x00]),
+        Err(Gs1Error::TooShort { needed: 2, got: 1 })
+    );
+}
+
+#[test]
+fn test_decode_xtid_header_strict() {
+    let xtid = decode_xtid_header_strict(&[0x00, 0x01]).unwrap();
+    assert_eq!(xtid, decode_xtid_header(&[0x00, 0x01]).unwrap());
+
+    // The bits reserved for future use are set
+    assert!(decode_xtid_header(&[0x40, 0x01]).is_ok());
+    assert_eq!(
+        decode_xtid_header_strict(&[0x40, 0x01]),
+        Err(Gs1Error::Parse)
+    );
+}
+
+#[test]
+fn test_encode_tid() {
+    let data = [0xE2, 0x80, 0x11, 0x05];
+    let tid = decode_tid(&data).unwrap();
+    assert_eq!(encode_tid(&tid), data.to_vec());
+
+    let tid = TID {
+        xtid: false,
+        security: true,
+        file: true,
+        mdid: 0x1FF,
+        tmid: 0xABC,
+    };
+    assert_eq!(decode_tid(&encode_tid(&tid)).unwrap(), tid);
+}
+
+#[test]
+fn test_tid_reader() {
+    // Impinj Monza 4QT with a 48-bit XTID serial number, read one word at a time
+    let data = [
+        0xE2, 0x80, 0x11, 0x05, 0x00, 0x01, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC,
+    ];
+    let mut reader = TidReader::new(&data[..2]);
+
+    assert_eq!(
+        reader.read_structure(),
+        Err(Gs1Error::TooShort { needed: 4, got: 2 })
+    );
+    reader.extend(&data[2..4]);
+    let tid = reader.read_structure().unwrap();
+    assert_eq!(tid.mdid, 0x1);
+    assert_eq!(tid.tmid, 0x105);
+    assert!(tid.xtid);
+
+    // The serial size isn't known until the XTID header has been read
+    assert_eq!(reader.read_serial(), Err(Gs1Error::Parse));
+    reader.extend(&data[4..6]);
+    assert_eq!(reader.read_xtid_header().unwrap().serial_size, 48);
+
+    reader.extend(&data[6..10]);
+    assert_eq!(
+        reader.read_serial(),
+        Err(Gs1Error::TooShort {
+            needed: 12,
+            got: 10
+        })
+    );
+    reader.extend(&data[10..]);
+    assert_eq!(reader.read_serial().unwrap(), data[6..]);
+    assert_eq!(reader.position(), 12);
+
+    // An invalid TID structure isn't consumed
+    let mut reader = TidReader::new(&[0xE3, 0x80, 0x11, 0x05]);
+    assert_eq!(reader.read_structure(), Err(Gs1Error::Parse));
+    assert_eq!(reader.position(), 0);
+}
+
+#[test]
+fn test_mdid_from_name() {
+    assert_eq!(mdid_from_name("Impinj"), Some(0x1));
+    assert_eq!(mdid_from_name("IMPINJ"), Some(0x1));
+    assert_eq!(mdid_from_name("nxp semiconductors"), Some(0x6));
+    assert_eq!(
+        mdid_from_name("Shanghai Fudan Microelectronics Group"),
+        Some(0x27)
+    );
+    assert_eq!(mdid_from_name("Unknown"), None);
+    assert_eq!(mdid_from_name("Acme Tags"), None);
+
+    for mdid in 1..=0x3C {
+        assert_eq!(mdid_from_name(mdid_name(&mdid)), Some(mdid));
+    }
+    assert_eq!(mdid_name(&0x1FF), "Unknown");
+}
+
+#[test]
+fn test_lookup_tables() {
+    assert_eq!(lookup_mdid(MDID_TABLE, 0x1), Some("Impinj"));
+    assert_eq!(lookup_mdid(MDID_TABLE, 0x1FF), None);
+    assert_eq!(lookup_tmid(TMID_TABLE, 0x1, 0x105), Some("Monza 4QT"));
+    assert_eq!(tmid_name(0x1, 0x105), "Monza 4QT");
+    assert_eq!(tmid_name(0x1, 0xFFF), "Unknown");
+
+    // A caller-supplied table
+    let mdids = [(0x1FF, "Acme Tags")];
+    let tmids = [(0x1FF, 0x001, "Acme 1")];
+    assert_eq!(lookup_mdid(&mdids, 0x1FF), Some("Acme Tags"));
+    assert_eq!(lookup_mdid(&mdids, 0x1), None);
+    assert_eq!(lookup_tmid(&tmids, 0x1FF, 0x001), Some("Acme 1"));
+}